@@ -0,0 +1,49 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use super_rusty::movesets::hyperbola_quintessence::HyperbolaQuintessenceAttacks;
+use super_rusty::movesets::kogge_stone::KoggeStoneAttacks;
+use super_rusty::movesets::pext::{has_bmi2, PextAttacks};
+use super_rusty::movesets::sliders::{FancyMagicAttacks, PlainMagicAttacks, SliderAttacks, SliderBackend};
+
+// Compares the throughput of the slider backends on the same random squares and occupancies.
+//
+//     cargo bench --bench sliders
+
+const LOOKUPS: usize = 1 << 20;
+const ROUNDS: usize = 10;
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(1);
+    // Sparse occupancies like in real positions
+    let positions: Vec<(usize, u64)> = (0..LOOKUPS)
+        .map(|_| (rng.gen_range(0..64), rng.gen::<u64>() & rng.gen::<u64>()))
+        .collect();
+
+    println!("{} queen look-ups per round, best of {} rounds, bmi2: {}", LOOKUPS, ROUNDS, has_bmi2());
+    benchmark("plain magics", &PlainMagicAttacks, &positions);
+    benchmark("fancy magics", &FancyMagicAttacks, &positions);
+    benchmark("pext", &PextAttacks::new(), &positions);
+    benchmark("software pext", &PextAttacks::software(), &positions);
+    benchmark("kogge-stone", &KoggeStoneAttacks, &positions);
+    benchmark("hyperbola quintessence", &HyperbolaQuintessenceAttacks, &positions);
+    // What rook_move, bishop_move and queen_move call by default
+    benchmark("resolved fancy magics", &SliderBackend::FancyMagics.functions(), &positions);
+}
+
+fn benchmark(name: &str, backend: &impl SliderAttacks, positions: &[(usize, u64)]) {
+    // The first round also fills the look-up tables
+    let mut best = f64::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let mut checksum = 0u64;
+        for &(index, occupancy) in positions {
+            checksum ^= backend.queen_attacks(black_box(index), black_box(occupancy));
+        }
+        black_box(checksum);
+        best = best.min(start.elapsed().as_secs_f64());
+    }
+    println!("{:>24}: {:6.2} ns per look-up, {:7.1} M look-ups/s", name, best * 1e9 / positions.len() as f64, positions.len() as f64 / best / 1e6);
+}
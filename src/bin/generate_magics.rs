@@ -0,0 +1,81 @@
+use std::fs;
+
+use super_rusty::chess::piece::PieceType;
+use super_rusty::movesets::magic_bitboards::{generate_all_blockerboards, generate_all_blockermasks, generate_all_moveboards, generate_better_magic_numbers, generate_magic_numbers};
+
+// Offline tool that searches magic numbers for rooks and bishops and writes them as Rust source.
+//
+//     cargo run --release --bin generate_magics -- [--seed N] [--better] [--attempts N] [PATH]
+//
+// The same seed always gives the same magic numbers. With --better it keeps looking for magic
+// numbers with fewer index bits, trying --attempts candidates for every bit. Without a path the
+// source is printed, with src/movesets/magic_numbers.rs as path the checked-in numbers are replaced.
+
+const DEFAULT_SEED: u64 = 1;
+const DEFAULT_ATTEMPTS: u64 = 100_000;
+
+fn main() {
+    let mut seed = DEFAULT_SEED;
+    let mut better = false;
+    let mut attempts = DEFAULT_ATTEMPTS;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = number_argument(&mut args, "--seed"),
+            "--attempts" => attempts = number_argument(&mut args, "--attempts"),
+            "--better" => better = true,
+            _ if arg.starts_with("--") => panic!("Unknown option {}", arg),
+            _ => path = Some(arg),
+        }
+    }
+
+    let mut command = format!("cargo run --release --bin generate_magics -- --seed {}", seed);
+    if better {
+        command += format!(" --better --attempts {}", attempts).as_str();
+    }
+    let mut source = String::from("// Magic numbers for the rook and bishop look-up tables and the number of index bits they use,\n");
+    source += "// indexed by square.\n";
+    source += format!("// Generated by `{}`, do not edit by hand.\n", command).as_str();
+
+    for (piece_type, name) in [(PieceType::Rook, "ROOK"), (PieceType::Bishop, "BISHOP")] {
+        let blockermasks = generate_all_blockermasks(piece_type);
+        let blockerboards = generate_all_blockerboards(&blockermasks);
+        let moveboards = generate_all_moveboards(&blockerboards, piece_type);
+        let (magic_numbers, index_bits) = if better {
+            generate_better_magic_numbers(&blockerboards, &moveboards, &blockermasks, seed, attempts)
+        } else {
+            let (magic_numbers, _) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks, seed);
+            (magic_numbers, blockermasks.map(|blockermask| blockermask.count_ones() as u8))
+        };
+
+        source += format!("\npub(crate) const {}_MAGICS: [u64; 64] = [\n", name).as_str();
+        for row in magic_numbers.chunks(4) {
+            let numbers: Vec<String> = row.iter().map(|magic| format!("0x{:016x}", magic)).collect();
+            source += format!("    {},\n", numbers.join(", ")).as_str();
+        }
+        source += "];\n";
+
+        source += format!("\npub(crate) const {}_INDEX_BITS: [u8; 64] = [\n", name).as_str();
+        for row in index_bits.chunks(8) {
+            let numbers: Vec<String> = row.iter().map(|bits| bits.to_string()).collect();
+            source += format!("    {},\n", numbers.join(", ")).as_str();
+        }
+        source += "];\n";
+
+        let table_size: usize = index_bits.iter().map(|&bits| 1 << bits).sum();
+        eprintln!("{} table entries: {}", name.to_lowercase(), table_size);
+    }
+
+    match path {
+        Some(path) => fs::write(&path, source).unwrap_or_else(|error| panic!("Could not write {}: {}", path, error)),
+        None => print!("{}", source),
+    }
+}
+
+fn number_argument(args: &mut impl Iterator<Item = String>, option: &str) -> u64 {
+    args.next()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("{} needs a number", option))
+}
@@ -1,4 +1,6 @@
-pub(crate) enum Castling {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Eq, Hash, Clone, Copy)]
+pub enum Castling {
     KingSide,
     QueenSide,
-}
\ No newline at end of file
+}
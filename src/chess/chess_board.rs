@@ -3,16 +3,24 @@ use super::piece::Color;
 use super::piece::PieceType;
 use super::piece::PieceInfo;
 use super::piece::SinglePieceInfo;
-use super::chess_move::Move;
+use super::chess_move::{castling_rook_squares, Move};
 use super::castling::Castling;
 
+// Squares involved in castling. The king always moves two files towards the rook.
+pub(crate) const WHITE_KING_START: u64 =         0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00001000;
+pub(crate) const WHITE_KING_SIDE_ROOK: u64 =     0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00000001;
+pub(crate) const WHITE_QUEEN_SIDE_ROOK: u64 =    0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_10000000;
+pub(crate) const BLACK_KING_START: u64 =         WHITE_KING_START << 56;
+pub(crate) const BLACK_KING_SIDE_ROOK: u64 =     WHITE_KING_SIDE_ROOK << 56;
+pub(crate) const BLACK_QUEEN_SIDE_ROOK: u64 =    WHITE_QUEEN_SIDE_ROOK << 56;
+
 /*
 Macro for creating uniform methods for accessing pieces (with mutable references).
  */
 macro_rules! piece_accessors {
     ($(($name:ident, $piece:ident, $color:ident)),* $(,)?) => {
         $(
-            pub(crate) fn $name(&mut self) -> &mut PieceInfo {
+            pub fn $name(&mut self) -> &mut PieceInfo {
                 self.get_mutable_piece_info(PieceType::$piece, Color::$color)
             }
         )*
@@ -25,20 +33,26 @@ Macro for creating uniform methods for accessing pieces (with immutable referenc
 macro_rules! piece_accessors_immutable {
     ($(($name:ident, $piece:ident, $color:ident)),* $(,)?) => {
         $(
-            pub(crate) fn $name(&self) -> &PieceInfo {
+            pub fn $name(&self) -> &PieceInfo {
                 self.get_piece_info(PieceType::$piece, Color::$color)
             }
         )*
     };
 }
 
-pub(crate) struct ChessBoard {
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChessBoard {
     pub piece_infos: HashMap<Color, HashMap<PieceType, PieceInfo>>,
     pub active_color: Color,
     pub white_king_side_castle: bool,
     pub white_queen_side_castle: bool,
     pub black_king_side_castle: bool,
     pub black_queen_side_castle: bool,
+    // The square a pawn skipped over with a double push on the last move, 0 if there is none
+    pub en_passant: u64,
+    // Number of half moves since the last capture or pawn move, for the fifty-move rule
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
     pub move_history: Vec<Move>,
 }
 
@@ -87,18 +101,37 @@ impl ChessBoard {
             black_king_side_castle: true,
             black_queen_side_castle: true,
             active_color: Color::White,
+            en_passant: 0,
+            halfmove_clock: 0,
+            fullmove_number: 1,
             move_history: Vec::new(),
         }
     }
 
     pub(crate) fn set_square(&mut self, piece_info: SinglePieceInfo) {
-        let position: u64 = 1 << (7 - piece_info.position_x) + (piece_info.position_y * 8);
+        let position: u64 = 1 << ((7 - piece_info.position_x) + (piece_info.position_y * 8));
         let mutable = self.piece_infos
             .get_mut(&piece_info.color).unwrap()
             .get_mut(&piece_info.piece_type).unwrap();
             mutable.positions |= position;
     }
 
+    pub(crate) fn clear_castling(&mut self) {
+        self.white_king_side_castle = false;
+        self.white_queen_side_castle = false;
+        self.black_king_side_castle = false;
+        self.black_queen_side_castle = false;
+    }
+
+    pub fn has_castling_right(&self, color: Color, castling: Castling) -> bool {
+        match (color, castling) {
+            (Color::White, Castling::KingSide) => self.white_king_side_castle,
+            (Color::White, Castling::QueenSide) => self.white_queen_side_castle,
+            (Color::Black, Castling::KingSide) => self.black_king_side_castle,
+            (Color::Black, Castling::QueenSide) => self.black_queen_side_castle,
+        }
+    }
+
     pub(crate) fn set_active_color(&mut self, color: Color) {
         self.active_color = color;
     }
@@ -138,7 +171,103 @@ impl ChessBoard {
             .get(&piece_type)
             .expect("PieceType not found in piece_infos for the given color")
     }
-    
+
+    /// Returns a bitboard with all pieces of the given color.
+    pub fn pieces_of(&self, color: Color) -> u64 {
+        match color {
+            Color::White => get_white_pieces(self),
+            Color::Black => get_black_pieces(self),
+        }
+    }
+
+    /// Returns a bitboard with all occupied squares.
+    pub fn occupancy(&self) -> u64 {
+        get_white_pieces(self) | get_black_pieces(self)
+    }
+
+    /// Returns the piece type and color of the piece on the given square, if any.
+    pub fn piece_at(&self, square: u64) -> Option<(PieceType, Color)> {
+        for color in [Color::White, Color::Black] {
+            for piece_type in PieceType::ALL {
+                if self.get_piece_info(piece_type, color).positions & square != 0 {
+                    return Some((piece_type, color));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the square of the king of the given color, or 0 if there is no king on the board.
+    pub fn king_square(&self, color: Color) -> u64 {
+        self.get_piece_info(PieceType::King, color).positions
+    }
+
+    /// Plays a move for the active color. The move is assumed to be legal, castling is given as a
+    /// king move of two files and en passant as a pawn capture on the en passant square.
+    pub fn make_move(&mut self, chess_move: &Move) {
+        let color = self.active_color;
+        let from = chess_move.old_position;
+        let to = chess_move.new_position;
+
+        // Remove a captured piece, which for en passant sits behind the target square
+        let mut is_capture = false;
+        if let Some((captured_type, captured_color)) = self.piece_at(to) {
+            self.get_mutable_piece_info(captured_type, captured_color).positions &= !to;
+            is_capture = true;
+        } else if chess_move.piece_type == PieceType::Pawn && to == self.en_passant {
+            let captured_square = if color == Color::White { to >> 8 } else { to << 8 };
+            self.get_mutable_piece_info(PieceType::Pawn, !color).positions &= !captured_square;
+            is_capture = true;
+        }
+
+        let moving = self.get_mutable_piece_info(chess_move.piece_type, color);
+        moving.positions &= !from;
+        match chess_move.promotion {
+            Some(promotion) => self.get_mutable_piece_info(promotion, color).positions |= to,
+            None => moving.positions |= to,
+        }
+
+        // Castling moves the rook to the square the king passed over
+        if chess_move.is_castling() {
+            let (rook_from, rook_to) = castling_rook_squares(to, from);
+            let rooks = self.get_mutable_piece_info(PieceType::Rook, color);
+            rooks.positions = (rooks.positions & !rook_from) | rook_to;
+        }
+
+        self.update_castling_rights(from | to);
+
+        self.en_passant = 0;
+        if chess_move.piece_type == PieceType::Pawn && (from == to << 16 || from == to >> 16) {
+            self.en_passant = if color == Color::White { from << 8 } else { from >> 8 };
+        }
+
+        if chess_move.piece_type == PieceType::Pawn || is_capture {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if color == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.active_color = !color;
+        self.move_history.push(chess_move.clone());
+    }
+
+    // A king or rook leaving its starting square, or a rook being captured there, loses the right
+    fn update_castling_rights(&mut self, touched: u64) {
+        if touched & (WHITE_KING_START | WHITE_KING_SIDE_ROOK) != 0 {
+            self.white_king_side_castle = false;
+        }
+        if touched & (WHITE_KING_START | WHITE_QUEEN_SIDE_ROOK) != 0 {
+            self.white_queen_side_castle = false;
+        }
+        if touched & (BLACK_KING_START | BLACK_KING_SIDE_ROOK) != 0 {
+            self.black_king_side_castle = false;
+        }
+        if touched & (BLACK_KING_START | BLACK_QUEEN_SIDE_ROOK) != 0 {
+            self.black_queen_side_castle = false;
+        }
+    }
 }
 
 
@@ -171,34 +300,35 @@ pub(crate) fn get_square(positions: u64, x: i32, y: i32) -> bool {
 }
 
 /// Takes a chess board and returns a bit board containing 1's on all places where there is an empty square. 
-fn get_empty_squares(chessboard : &mut ChessBoard) -> u64 {
+pub(crate) fn get_empty_squares(chessboard : &ChessBoard) -> u64 {
     !(get_black_pieces(chessboard) | get_white_pieces(chessboard))
 }
 
-/// Takes a chess board and returns a bit board containing 1's on all places where there is an empty square. 
-fn get_white_pieces(chessboard : &mut ChessBoard) -> u64 {
-    chessboard.white_pawns().positions |
-    chessboard.white_rooks().positions |
-    chessboard.white_knights().positions |
-    chessboard.white_bishops().positions |
-    chessboard.white_queens().positions |
-    chessboard.white_kings().positions
+/// Takes a chess board and returns a bit board containing 1's on all places where there is a white piece. 
+pub(crate) fn get_white_pieces(chessboard : &ChessBoard) -> u64 {
+    chessboard.white_pawns_immutable().positions |
+    chessboard.white_rooks_immutable().positions |
+    chessboard.white_knights_immutable().positions |
+    chessboard.white_bishops_immutable().positions |
+    chessboard.white_queens_immutable().positions |
+    chessboard.white_kings_immutable().positions
 }
 
-/// Takes a chess board and returns a bit board containing 1's on all places where there is an empty square. 
-fn get_black_pieces(chessboard : &mut ChessBoard) -> u64 {
-    chessboard.black_pawns().positions |
-    chessboard.black_rooks().positions |
-    chessboard.black_knights().positions |
-    chessboard.black_bishops().positions |
-    chessboard.black_queens().positions |
-    chessboard.black_kings().positions
+/// Takes a chess board and returns a bit board containing 1's on all places where there is a black piece. 
+pub(crate) fn get_black_pieces(chessboard : &ChessBoard) -> u64 {
+    chessboard.black_pawns_immutable().positions |
+    chessboard.black_rooks_immutable().positions |
+    chessboard.black_knights_immutable().positions |
+    chessboard.black_bishops_immutable().positions |
+    chessboard.black_queens_immutable().positions |
+    chessboard.black_kings_immutable().positions
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::diagram_reader::read_bitboard;
 
     #[test]
     fn test_new() {
@@ -222,24 +352,24 @@ mod tests {
     
     #[test]
     fn test_get_empty_squares() {
-        let mut chessboard = ChessBoard::new(false);
-        let result = get_empty_squares(&mut chessboard);
+        let chessboard = ChessBoard::new(false);
+        let result = get_empty_squares(&chessboard);
         let expected = 0b00000000_00000000_11111111_11111111_11111111_11111111_00000000_00000000;
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_get_white_pieces(){
-        let mut chessboard = ChessBoard::new(false);
-        let result = get_white_pieces(&mut chessboard);
+        let chessboard = ChessBoard::new(false);
+        let result = get_white_pieces(&chessboard);
         let expected = 0b0000000_00000000_00000000_00000000_00000000_00000000_11111111_11111111;
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_get_black_pieces(){
-        let mut chessboard = ChessBoard::new(false);
-        let result = get_black_pieces(&mut chessboard);
+        let chessboard = ChessBoard::new(false);
+        let result = get_black_pieces(&chessboard);
         let expected = 0b11111111_11111111_00000000_00000000_00000000_00000000_00000000_00000000;
         assert_eq!(result, expected);
     }
@@ -254,6 +384,16 @@ mod tests {
             position_y: 4
         });
 
-        assert_eq!(chessboard.white_pawns().positions, 0b00000000_00000000_00000000_01000000_00000000_00000000_11111111_00000000);
+        let expected = read_bitboard("
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . x . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            x x x x x x x x
+            . . . . . . . .
+        ");
+        assert_eq!(chessboard.white_pawns().positions, expected);
     }
 }
\ No newline at end of file
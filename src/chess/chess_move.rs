@@ -1,7 +1,108 @@
+use super::chess_board::ChessBoard;
 use super::piece::PieceType;
+use crate::utils::board_utils::{parse_square, square_name};
 
-pub(crate) struct Move {
+#[derive(PartialEq, Debug, Eq, Hash, Clone)]
+pub struct Move {
     pub piece_type:PieceType,
     pub old_position: u64,
-    pub new_position: u64
-}
\ No newline at end of file
+    pub new_position: u64,
+    // The piece a pawn turns into when it reaches the last rank
+    pub promotion: Option<PieceType>,
+}
+
+impl Move {
+    pub fn new(piece_type: PieceType, old_position: u64, new_position: u64) -> Move {
+        Move { piece_type, old_position, new_position, promotion: None }
+    }
+
+    pub fn new_promotion(old_position: u64, new_position: u64, promotion: PieceType) -> Move {
+        Move { piece_type: PieceType::Pawn, old_position, new_position, promotion: Some(promotion) }
+    }
+
+    /// Returns true if the move castles, the only king move of two files.
+    pub fn is_castling(&self) -> bool {
+        self.piece_type == PieceType::King
+            && (self.old_position == self.new_position << 2 || self.old_position == self.new_position >> 2)
+    }
+
+    /// Returns the move in UCI notation, e.g. `e2e4` or `e7e8q`.
+    pub fn to_uci(&self) -> String {
+        let mut result = square_name(self.old_position) + &square_name(self.new_position);
+        if let Some(promotion) = self.promotion {
+            result.push(promotion.letter().to_ascii_lowercase());
+        }
+        result
+    }
+
+    /// Finds the legal move in the given position that matches a UCI string such as `e2e4` or `e7e8q`.
+    pub fn from_uci(chess_board: &ChessBoard, uci: &str) -> Option<Move> {
+        let (old_position, new_position, promotion) = parse_uci(uci)?;
+        chess_board.legal_moves().into_iter().find(|chess_move| {
+            chess_move.old_position == old_position
+                && chess_move.new_position == new_position
+                && chess_move.promotion == promotion
+        })
+    }
+}
+
+// The squares the rook moves from and to when the king castles from `from` to `to`
+pub(crate) fn castling_rook_squares(to: u64, from: u64) -> (u64, u64) {
+    if to < from { (to >> 1, to << 1) } else { (to << 2, to >> 1) }
+}
+
+// Splits a UCI string into the from and to squares and the optional promotion piece
+pub(crate) fn parse_uci(uci: &str) -> Option<(u64, u64, Option<PieceType>)> {
+    if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+        return None;
+    }
+    let old_position = parse_square(&uci[0..2])?;
+    let new_position = parse_square(&uci[2..4])?;
+    let promotion = match uci[4..].chars().next() {
+        Some(letter) if letter.is_ascii_lowercase() => match PieceType::from_letter(letter)? {
+            PieceType::Pawn | PieceType::King => return None,
+            piece_type => Some(piece_type),
+        },
+        Some(_) => return None,
+        None => None,
+    };
+    Some((old_position, new_position, promotion))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_uci() {
+        let e2 = 0b00000000_00000000_00000000_00000000_00000000_00000000_00001000_00000000;
+        let e4 = 0b00000000_00000000_00000000_00000000_00001000_00000000_00000000_00000000;
+        assert_eq!("e2e4", Move::new(PieceType::Pawn, e2, e4).to_uci());
+
+        let a7 = 0b00000000_10000000_00000000_00000000_00000000_00000000_00000000_00000000;
+        let a8 = 0b10000000_00000000_00000000_00000000_00000000_00000000_00000000_00000000;
+        assert_eq!("a7a8q", Move::new_promotion(a7, a8, PieceType::Queen).to_uci());
+    }
+
+    #[test]
+    fn test_from_uci() {
+        let chess_board = ChessBoard::new(false);
+        let chess_move = Move::from_uci(&chess_board, "g1f3").unwrap();
+        assert_eq!(PieceType::Knight, chess_move.piece_type);
+        assert_eq!("g1f3", chess_move.to_uci());
+        assert_eq!(None, Move::from_uci(&chess_board, "e2e5"));
+        assert_eq!(None, Move::from_uci(&chess_board, "e2e4k"));
+        assert_eq!(None, Move::from_uci(&chess_board, "e2"));
+    }
+
+    #[test]
+    fn test_castling() {
+        let square = |name| parse_square(name).unwrap();
+        assert!(Move::new(PieceType::King, square("e1"), square("g1")).is_castling());
+        assert!(Move::new(PieceType::King, square("e8"), square("c8")).is_castling());
+        assert!(!Move::new(PieceType::King, square("e1"), square("f1")).is_castling());
+        assert!(!Move::new(PieceType::Rook, square("a1"), square("c1")).is_castling());
+        assert_eq!((square("h1"), square("f1")), castling_rook_squares(square("g1"), square("e1")));
+        assert_eq!((square("a8"), square("d8")), castling_rook_squares(square("c8"), square("e8")));
+    }
+}
@@ -0,0 +1,140 @@
+use super::chess_board::ChessBoard;
+use super::chess_move::Move;
+use super::piece::Color;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unknown,
+}
+
+impl GameResult {
+    /// The result token as written in PGN tags and at the end of the movetext.
+    pub fn token(self) -> &'static str {
+        match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Unknown => "*",
+        }
+    }
+}
+
+/// A move in a game together with its annotations.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MoveNode {
+    pub chess_move: Move,
+    // Numeric Annotation Glyphs, e.g. 1 for "!" and 4 for "??"
+    pub nags: Vec<u8>,
+    // Comment shown before the move, e.g. at the start of a variation
+    pub pre_comment: Option<String>,
+    // Comment shown after the move
+    pub comment: Option<String>,
+    // Alternative lines that could have been played instead of this move
+    pub variations: Vec<Vec<MoveNode>>,
+}
+
+impl MoveNode {
+    pub fn new(chess_move: Move) -> MoveNode {
+        MoveNode { chess_move, nags: Vec::new(), pre_comment: None, comment: None, variations: Vec::new() }
+    }
+}
+
+/// A played or analysed game: the tags, the starting position and the annotated main line.
+#[derive(PartialEq, Debug, Clone)]
+pub struct GameRecord {
+    // Tags in the order they should be written, e.g. ("Event", "Club championship")
+    pub tags: Vec<(String, String)>,
+    pub starting_board: ChessBoard,
+    // Comment before the first move
+    pub comment: Option<String>,
+    pub moves: Vec<MoveNode>,
+    pub result: GameResult,
+}
+
+impl GameRecord {
+    pub fn new(starting_board: ChessBoard) -> GameRecord {
+        GameRecord {
+            tags: Vec::new(),
+            starting_board,
+            comment: None,
+            moves: Vec::new(),
+            result: GameResult::Unknown,
+        }
+    }
+
+    /// Creates a record of the moves played from the starting board, e.g. its `move_history`.
+    /// A game that ends in checkmate or stalemate gets the matching result.
+    pub fn from_moves(starting_board: &ChessBoard, moves: &[Move]) -> GameRecord {
+        let mut starting_board = starting_board.clone();
+        starting_board.move_history.clear();
+        let mut record = GameRecord::new(starting_board);
+        record.moves = moves.iter().cloned().map(MoveNode::new).collect();
+
+        let final_board = record.final_board();
+        if final_board.is_checkmate() {
+            record.result = if final_board.active_color == Color::White { GameResult::BlackWins } else { GameResult::WhiteWins };
+        } else if final_board.is_stalemate() {
+            record.result = GameResult::Draw;
+        }
+        record
+    }
+
+    /// Returns the value of a tag, if it is set.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a tag, replacing the value if the tag already exists.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(key, _)| key == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Returns the position after all moves of the main line.
+    pub fn final_board(&self) -> ChessBoard {
+        let mut chess_board = self.starting_board.clone();
+        for node in &self.moves {
+            chess_board.make_move(&node.chess_move);
+        }
+        chess_board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::piece::PieceType;
+    use crate::utils::board_utils::parse_square;
+
+    fn pawn_move(from: &str, to: &str) -> Move {
+        Move::new(PieceType::Pawn, parse_square(from).unwrap(), parse_square(to).unwrap())
+    }
+
+    #[test]
+    fn test_from_moves_detects_checkmate() {
+        let moves = vec![
+            pawn_move("f2", "f3"),
+            pawn_move("e7", "e5"),
+            pawn_move("g2", "g4"),
+            Move::new(PieceType::Queen, parse_square("d8").unwrap(), parse_square("h4").unwrap()),
+        ];
+        let record = GameRecord::from_moves(&ChessBoard::new(false), &moves);
+        assert_eq!(4, record.moves.len());
+        assert_eq!(GameResult::BlackWins, record.result);
+    }
+
+    #[test]
+    fn test_set_tag() {
+        let mut record = GameRecord::new(ChessBoard::new(false));
+        record.set_tag("White", "Morphy");
+        record.set_tag("White", "Anderssen");
+        assert_eq!(Some("Anderssen"), record.tag("White"));
+        assert_eq!(1, record.tags.len());
+    }
+}
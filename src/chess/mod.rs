@@ -2,4 +2,8 @@ pub mod piece;
 pub mod chess_move;
 pub mod castling;
 pub mod chess_board;
-pub mod visualization;
\ No newline at end of file
+pub mod visualization;
+pub mod game_record;
+pub mod svg;
+pub mod raster;
+pub(crate) mod piece_shapes;
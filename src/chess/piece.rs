@@ -1,6 +1,7 @@
 use std::ops::Not;
-#[derive(PartialEq, Debug, Eq, Hash, Clone)]
-pub  enum Color{
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Eq, Hash, Clone, Copy)]
+pub enum Color{
     Black,
     White
 }
@@ -16,7 +17,8 @@ impl Not for Color {
     }
 }
 
-#[derive(PartialEq, Debug, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug, Eq, Hash, Clone, Copy)]
 pub enum PieceType{
     Pawn,
     Knight,
@@ -26,8 +28,45 @@ pub enum PieceType{
     King,
 }
 
-#[derive(PartialEq, Debug)]
-pub(crate) struct PieceInfo {
+impl PieceType {
+    /// All piece types, in the order pieces are usually listed (pawn first, king last).
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ];
+
+    /// The upper case English letter used for this piece in FEN and SAN.
+    pub fn letter(self) -> char {
+        match self {
+            PieceType::Pawn => 'P',
+            PieceType::Knight => 'N',
+            PieceType::Bishop => 'B',
+            PieceType::Rook => 'R',
+            PieceType::Queen => 'Q',
+            PieceType::King => 'K',
+        }
+    }
+
+    /// Inverse of `letter`, accepting both upper and lower case.
+    pub fn from_letter(letter: char) -> Option<PieceType> {
+        match letter.to_ascii_uppercase() {
+            'P' => Some(PieceType::Pawn),
+            'N' => Some(PieceType::Knight),
+            'B' => Some(PieceType::Bishop),
+            'R' => Some(PieceType::Rook),
+            'Q' => Some(PieceType::Queen),
+            'K' => Some(PieceType::King),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PieceInfo {
     // Bitboard representing the positions of all pieces of this type on the board
     pub positions: u64,
     // Bitboard representing the possible moves for a piece of this type at a given position
@@ -124,7 +163,7 @@ impl PieceInfo {
 
 
 #[derive(PartialEq, Debug)]
-pub(crate) struct SinglePieceInfo {
+pub struct SinglePieceInfo {
     pub piece_type: PieceType,
     pub color: Color,
     pub position_x: usize,
@@ -0,0 +1,80 @@
+use super::piece::PieceType;
+
+// Vector outlines of the pieces, shared by the image renderers so no font or image files are needed.
+// Coordinates are in a 45x45 box per square with the origin in the top left corner.
+
+pub(crate) const PIECE_SIZE: f32 = 45.0;
+
+pub(crate) enum Shape {
+    Polygon(&'static [(f32, f32)]),
+    // Center and radius
+    Circle((f32, f32), f32),
+}
+
+const PAWN: [Shape; 2] = [
+    Shape::Polygon(&[
+        (18.0, 21.0), (27.0, 21.0), (30.0, 32.0), (34.0, 34.0), (34.0, 38.0),
+        (11.0, 38.0), (11.0, 34.0), (15.0, 32.0),
+    ]),
+    Shape::Circle((22.5, 15.5), 5.5),
+];
+
+const KNIGHT: [Shape; 1] = [
+    Shape::Polygon(&[
+        (14.0, 38.0), (34.0, 38.0), (33.0, 26.0), (31.0, 17.0), (26.0, 11.0), (23.0, 6.0),
+        (20.0, 10.0), (15.0, 13.0), (9.0, 22.0), (11.0, 26.0), (15.0, 25.0), (21.0, 21.0),
+        (15.0, 31.0),
+    ]),
+];
+
+const BISHOP: [Shape; 2] = [
+    Shape::Polygon(&[
+        (22.5, 11.0), (28.0, 17.0), (27.0, 24.0), (29.0, 32.0), (34.0, 34.0), (34.0, 38.0),
+        (11.0, 38.0), (11.0, 34.0), (16.0, 32.0), (18.0, 24.0), (17.0, 17.0),
+    ]),
+    Shape::Circle((22.5, 8.5), 2.5),
+];
+
+const ROOK: [Shape; 1] = [
+    Shape::Polygon(&[
+        (11.0, 9.0), (15.0, 9.0), (15.0, 12.0), (20.0, 12.0), (20.0, 9.0), (25.0, 9.0),
+        (25.0, 12.0), (30.0, 12.0), (30.0, 9.0), (34.0, 9.0), (34.0, 15.0), (31.0, 17.0),
+        (31.0, 30.0), (34.0, 32.0), (34.0, 38.0), (11.0, 38.0), (11.0, 32.0), (14.0, 30.0),
+        (14.0, 17.0), (11.0, 15.0),
+    ]),
+];
+
+const QUEEN: [Shape; 6] = [
+    Shape::Polygon(&[
+        (9.0, 14.0), (14.0, 26.0), (15.0, 13.0), (19.0, 25.0), (22.5, 11.0), (26.0, 25.0),
+        (30.0, 13.0), (31.0, 26.0), (36.0, 14.0), (31.0, 32.0), (34.0, 34.0), (34.0, 38.0),
+        (11.0, 38.0), (11.0, 34.0), (14.0, 32.0),
+    ]),
+    Shape::Circle((9.0, 13.0), 2.0),
+    Shape::Circle((15.0, 12.0), 2.0),
+    Shape::Circle((22.5, 10.0), 2.0),
+    Shape::Circle((30.0, 12.0), 2.0),
+    Shape::Circle((36.0, 13.0), 2.0),
+];
+
+const KING: [Shape; 2] = [
+    Shape::Polygon(&[
+        (21.0, 5.0), (24.0, 5.0), (24.0, 8.0), (27.0, 8.0), (27.0, 11.0), (24.0, 11.0),
+        (24.0, 15.0), (21.0, 15.0), (21.0, 11.0), (18.0, 11.0), (18.0, 8.0), (21.0, 8.0),
+    ]),
+    Shape::Polygon(&[
+        (21.0, 16.0), (24.0, 16.0), (32.0, 20.0), (35.0, 25.0), (31.0, 32.0), (34.0, 34.0),
+        (34.0, 38.0), (11.0, 38.0), (11.0, 34.0), (14.0, 32.0), (10.0, 25.0), (13.0, 20.0),
+    ]),
+];
+
+pub(crate) fn piece_shapes(piece_type: PieceType) -> &'static [Shape] {
+    match piece_type {
+        PieceType::Pawn => &PAWN,
+        PieceType::Knight => &KNIGHT,
+        PieceType::Bishop => &BISHOP,
+        PieceType::Rook => &ROOK,
+        PieceType::Queen => &QUEEN,
+        PieceType::King => &KING,
+    }
+}
@@ -0,0 +1,282 @@
+use super::chess_board::ChessBoard;
+use super::piece::{Color, PieceType};
+use super::piece_shapes::{piece_shapes, Shape, PIECE_SIZE};
+use super::svg::{piece_colors, square_origin, DARK_SQUARE_COLOR, HIGHLIGHT_COLOR, LIGHT_SQUARE_COLOR};
+use super::visualization::{check_squares, last_move_squares};
+use crate::utils::board_utils::{square_from_xy, squares};
+
+// Renders a board to pixels with a small anti-aliased polygon rasterizer, drawing the same piece
+// shapes and colors as the SVG renderer. `render_png` encodes the pixels as a PNG image.
+
+pub const CHECK_COLOR: [u8; 3] = [0xe0, 0x4f, 0x4f];
+
+// Number of sample rows per pixel row used for anti-aliasing
+const SAMPLES: usize = 4;
+// Number of straight segments used to draw a circle
+const CIRCLE_SEGMENTS: usize = 32;
+const STROKE_WIDTH: f32 = 1.5;
+const HIGHLIGHT_OPACITY: f32 = 0.6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterOptions {
+    // Width and height of the image in pixels
+    pub size: u32,
+    // The side shown at the bottom of the board
+    pub perspective: Color,
+    // Highlight the from and to squares of the last move in the move history
+    pub highlight_last_move: bool,
+    // Highlight a king in check and the pieces giving check
+    pub highlight_check: bool,
+    // Other squares to color, each bitboard with its own RGB color
+    pub highlights: Vec<(u64, [u8; 3])>,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        RasterOptions {
+            size: 360,
+            perspective: Color::White,
+            highlight_last_move: false,
+            highlight_check: false,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+/// Draws the board as RGB pixels, row by row from the top left, three bytes per pixel.
+pub fn render_pixels(chess_board: &ChessBoard, options: &RasterOptions) -> Vec<u8> {
+    let mut canvas = Canvas::new(options.size as usize, options.size as f32 / (8.0 * PIECE_SIZE));
+
+    for y in 0..8 {
+        for x in 0..8 {
+            let color = if (x + y) % 2 == 0 { DARK_SQUARE_COLOR } else { LIGHT_SQUARE_COLOR };
+            canvas.fill_square(square_from_xy(x, y), options.perspective, parse_color(color), 1.0);
+        }
+    }
+
+    let mut highlights = options.highlights.clone();
+    if options.highlight_last_move {
+        highlights.push((last_move_squares(chess_board), parse_color(HIGHLIGHT_COLOR)));
+    }
+    if options.highlight_check {
+        highlights.push((check_squares(chess_board), CHECK_COLOR));
+    }
+    for (bitboard, color) in highlights {
+        for square in squares(bitboard) {
+            canvas.fill_square(square, options.perspective, color, HIGHLIGHT_OPACITY);
+        }
+    }
+
+    for square in squares(chess_board.occupancy()) {
+        let (piece_type, color) = chess_board.piece_at(square).unwrap();
+        canvas.draw_piece(piece_type, color, square_origin(square, options.perspective));
+    }
+
+    canvas.pixels
+}
+
+/// Draws the board as a PNG image. Fails for a size of zero, which PNG does not allow.
+#[cfg(feature = "png")]
+pub fn render_png(chess_board: &ChessBoard, options: &RasterOptions) -> Result<Vec<u8>, String> {
+    if options.size == 0 {
+        return Err("PNG image size must be at least one pixel".to_string());
+    }
+    let pixels = render_pixels(chess_board, options);
+    let mut image = Vec::new();
+    let mut encoder = png::Encoder::new(&mut image, options.size, options.size);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|error| format!("Failed to write PNG header: {}", error))?;
+    writer.write_image_data(&pixels).map_err(|error| format!("Failed to write PNG image data: {}", error))?;
+    writer.finish().map_err(|error| format!("Failed to finish PNG image: {}", error))?;
+    Ok(image)
+}
+
+// Parses colors written as "#rrggbb"
+pub(crate) fn parse_color(color: &str) -> [u8; 3] {
+    let channel = |index: usize| u8::from_str_radix(&color[1 + 2 * index..3 + 2 * index], 16).unwrap();
+    [channel(0), channel(1), channel(2)]
+}
+
+struct Canvas {
+    size: usize,
+    // Pixels per board unit
+    scale: f32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(size: usize, scale: f32) -> Self {
+        Canvas { size, scale, pixels: vec![0; size * size * 3] }
+    }
+
+    fn fill_square(&mut self, square: u64, perspective: Color, color: [u8; 3], opacity: f32) {
+        let (left, top) = square_origin(square, perspective);
+        let (right, bottom) = (left + PIECE_SIZE, top + PIECE_SIZE);
+        self.fill_polygon(&[(left, top), (right, top), (right, bottom), (left, bottom)], color, opacity);
+    }
+
+    fn draw_piece(&mut self, piece_type: PieceType, color: Color, (left, top): (f32, f32)) {
+        let (fill, stroke) = piece_colors(color);
+        let (fill, stroke) = (parse_color(fill), parse_color(stroke));
+        for shape in piece_shapes(piece_type) {
+            match shape {
+                Shape::Polygon(vertices) => {
+                    let vertices: Vec<(f32, f32)> = vertices.iter().map(|&(x, y)| (left + x, top + y)).collect();
+                    self.fill_polygon(&vertices, fill, 1.0);
+                    self.stroke_polygon(&vertices, stroke);
+                }
+                Shape::Circle((x, y), radius) => {
+                    let center = (left + x, top + y);
+                    self.fill_polygon(&circle(center, radius + STROKE_WIDTH / 2.0), stroke, 1.0);
+                    self.fill_polygon(&circle(center, radius - STROKE_WIDTH / 2.0), fill, 1.0);
+                }
+            }
+        }
+    }
+
+    // Draws every edge as a thin rectangle with a round joint at the corners
+    fn stroke_polygon(&mut self, vertices: &[(f32, f32)], color: [u8; 3]) {
+        for (index, &from) in vertices.iter().enumerate() {
+            let to = vertices[(index + 1) % vertices.len()];
+            let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+            let length = (dx * dx + dy * dy).sqrt();
+            let (nx, ny) = (-dy / length * STROKE_WIDTH / 2.0, dx / length * STROKE_WIDTH / 2.0);
+            self.fill_polygon(&[(from.0 + nx, from.1 + ny), (to.0 + nx, to.1 + ny), (to.0 - nx, to.1 - ny), (from.0 - nx, from.1 - ny)], color, 1.0);
+            self.fill_polygon(&circle(from, STROKE_WIDTH / 2.0), color, 1.0);
+        }
+    }
+
+    // Fills a polygon given in board units with the even-odd rule. For every sample row the spans
+    // between edge crossings add their exact horizontal overlap to the coverage of each pixel.
+    fn fill_polygon(&mut self, vertices: &[(f32, f32)], color: [u8; 3], opacity: f32) {
+        let vertices: Vec<(f32, f32)> = vertices.iter().map(|&(x, y)| (x * self.scale, y * self.scale)).collect();
+        let min_y = vertices.iter().map(|vertex| vertex.1).fold(f32::MAX, f32::min).max(0.0) as usize;
+        let max_y = (vertices.iter().map(|vertex| vertex.1).fold(f32::MIN, f32::max).ceil() as usize).min(self.size);
+
+        let mut coverage = vec![0.0; self.size];
+        let mut crossings = Vec::new();
+        for y in min_y..max_y {
+            coverage.iter_mut().for_each(|value| *value = 0.0);
+            for sample in 0..SAMPLES {
+                let sample_y = y as f32 + (sample as f32 + 0.5) / SAMPLES as f32;
+                crossings.clear();
+                for (index, &(x0, y0)) in vertices.iter().enumerate() {
+                    let (x1, y1) = vertices[(index + 1) % vertices.len()];
+                    if (y0 <= sample_y) != (y1 <= sample_y) {
+                        crossings.push(x0 + (sample_y - y0) / (y1 - y0) * (x1 - x0));
+                    }
+                }
+                crossings.sort_by(f32::total_cmp);
+                for span in crossings.chunks_exact(2) {
+                    self.add_span(&mut coverage, span[0], span[1]);
+                }
+            }
+            for (x, &value) in coverage.iter().enumerate() {
+                if value > 0.0 {
+                    self.blend(x, y, color, (value / SAMPLES as f32).min(1.0) * opacity);
+                }
+            }
+        }
+    }
+
+    fn add_span(&self, coverage: &mut [f32], start: f32, end: f32) {
+        let start = start.clamp(0.0, self.size as f32);
+        let end = end.clamp(0.0, self.size as f32);
+        let mut x = start.floor() as usize;
+        while (x as f32) < end && x < self.size {
+            let left = start.max(x as f32);
+            let right = end.min(x as f32 + 1.0);
+            coverage[x] += right - left;
+            x += 1;
+        }
+    }
+
+    fn blend(&mut self, x: usize, y: usize, color: [u8; 3], alpha: f32) {
+        let offset = (y * self.size + x) * 3;
+        for (pixel, &channel) in self.pixels[offset..offset + 3].iter_mut().zip(color.iter()) {
+            let old = *pixel as f32;
+            *pixel = (old + (channel as f32 - old) * alpha).round() as u8;
+        }
+    }
+}
+
+fn circle((x, y): (f32, f32), radius: f32) -> Vec<(f32, f32)> {
+    (0..CIRCLE_SEGMENTS)
+        .map(|index| {
+            let angle = index as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+            (x + radius * angle.cos(), y + radius * angle.sin())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fen_reader::read_fen;
+
+    // The color of the pixel at the given position
+    fn pixel(pixels: &[u8], size: u32, x: u32, y: u32) -> [u8; 3] {
+        let offset = ((y * size + x) * 3) as usize;
+        [pixels[offset], pixels[offset + 1], pixels[offset + 2]]
+    }
+
+    #[test]
+    fn test_render_pixels_squares_and_pieces() {
+        let options = RasterOptions { size: 80, ..RasterOptions::default() };
+        let pixels = render_pixels(&ChessBoard::new(false), &options);
+        assert_eq!(80 * 80 * 3, pixels.len());
+        // Corners of a8 (light) and h8 (dark), and the empty d5 square (light)
+        assert_eq!(parse_color(LIGHT_SQUARE_COLOR), pixel(&pixels, 80, 0, 0));
+        assert_eq!(parse_color(DARK_SQUARE_COLOR), pixel(&pixels, 80, 79, 0));
+        assert_eq!(parse_color(LIGHT_SQUARE_COLOR), pixel(&pixels, 80, 35, 35));
+        // The middle of the white king on e1 and the black king on e8
+        assert_eq!([0xff, 0xff, 0xff], pixel(&pixels, 80, 45, 76));
+        assert_eq!([0, 0, 0], pixel(&pixels, 80, 45, 6));
+    }
+
+    #[test]
+    fn test_render_pixels_orientation() {
+        let chess_board = read_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        let options = RasterOptions { size: 80, perspective: Color::Black, ..RasterOptions::default() };
+        let pixels = render_pixels(&chess_board, &options);
+        // From Black the white king is in the fourth column of the top row
+        assert_eq!([0xff, 0xff, 0xff], pixel(&pixels, 80, 35, 6));
+        assert_eq!([0, 0, 0], pixel(&pixels, 80, 35, 76));
+        assert_eq!(parse_color(LIGHT_SQUARE_COLOR), pixel(&pixels, 80, 0, 0));
+    }
+
+    #[test]
+    fn test_render_pixels_highlights() {
+        let mut chess_board = read_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        let a1 = square_from_xy(0, 0);
+        let a8 = square_from_xy(0, 7);
+        chess_board.make_move(&crate::chess::chess_move::Move::new(PieceType::Rook, a1, a8));
+        let options = RasterOptions { size: 80, highlight_last_move: true, highlight_check: true, ..RasterOptions::default() };
+        let pixels = render_pixels(&chess_board, &options);
+        let plain = render_pixels(&chess_board, &RasterOptions { size: 80, ..RasterOptions::default() });
+        // The corner of a1 changes color for the last move, the corner of e8 for the check
+        assert_ne!(pixel(&plain, 80, 0, 79), pixel(&pixels, 80, 0, 79));
+        assert_ne!(pixel(&plain, 80, 40, 0), pixel(&pixels, 80, 40, 0));
+        assert_eq!(pixel(&plain, 80, 79, 79), pixel(&pixels, 80, 79, 79));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_render_png() {
+        let options = RasterOptions { size: 64, ..RasterOptions::default() };
+        let image = render_png(&ChessBoard::new(false), &options).unwrap();
+        assert_eq!(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'], &image[..8]);
+
+        let decoder = png::Decoder::new(image.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((64, 64), (reader.info().width, reader.info().height));
+        assert_eq!(render_pixels(&ChessBoard::new(false), &options), pixels);
+
+        let empty = RasterOptions { size: 0, ..RasterOptions::default() };
+        assert!(render_png(&ChessBoard::new(false), &empty).is_err());
+        assert!(render_pixels(&ChessBoard::new(false), &empty).is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+use super::chess_board::ChessBoard;
+use super::piece::{Color, PieceType};
+use super::piece_shapes::{piece_shapes, Shape, PIECE_SIZE};
+use crate::utils::board_utils::{square_from_xy, square_to_xy, squares};
+
+// Renders a board as a standalone SVG image. Pieces are drawn from the built in shapes, so the
+// image can be embedded anywhere without fonts or other files.
+
+pub(crate) const LIGHT_SQUARE_COLOR: &str = "#f0d9b5";
+pub(crate) const DARK_SQUARE_COLOR: &str = "#b58863";
+pub const HIGHLIGHT_COLOR: &str = "#cdd26a";
+pub const ARROW_COLOR: &str = "#15781b";
+
+// Space around the board for the coordinates
+const MARGIN: f32 = 20.0;
+
+/// An arrow from the center of one square to the center of another, e.g. for the best move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arrow {
+    pub from: u64,
+    pub to: u64,
+    pub color: String,
+}
+
+impl Arrow {
+    pub fn new(from: u64, to: u64) -> Self {
+        Arrow { from, to, color: ARROW_COLOR.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    // Width and height of the image in pixels
+    pub size: u32,
+    // The side shown at the bottom of the board
+    pub perspective: Color,
+    // Show the file letters and rank numbers next to the board
+    pub coordinates: bool,
+    // Squares to color, each bitboard with its own color
+    pub highlights: Vec<(u64, String)>,
+    pub arrows: Vec<Arrow>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            size: 400,
+            perspective: Color::White,
+            coordinates: true,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+        }
+    }
+}
+
+/// Draws the board as an SVG document.
+pub fn render_svg(chess_board: &ChessBoard, options: &SvgOptions) -> String {
+    let (origin, view_size) = if options.coordinates { (-MARGIN, 8.0 * PIECE_SIZE + 2.0 * MARGIN) } else { (0.0, 8.0 * PIECE_SIZE) };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+        options.size, options.size, origin, origin, view_size, view_size
+    );
+
+    svg += piece_definitions().as_str();
+    svg += board_squares(options.perspective).as_str();
+    svg += square_highlights(&options.highlights, options.perspective).as_str();
+    if options.coordinates {
+        svg += coordinates(options.perspective).as_str();
+    }
+    svg += board_pieces(chess_board, options.perspective).as_str();
+
+    for arrow in &options.arrows {
+        let from = square_center(arrow.from, options.perspective);
+        let to = square_center(arrow.to, options.perspective);
+        svg += format!("<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"0.8\"/>\n", points(&arrow_polygon(from, to)), arrow.color).as_str();
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+/// The `<defs>` block with every piece, which `board_pieces` refers to.
+pub(crate) fn piece_definitions() -> String {
+    let mut svg = "<defs>\n".to_string();
+    for color in [Color::White, Color::Black] {
+        for piece_type in PieceType::ALL {
+            svg += piece_definition(piece_type, color).as_str();
+        }
+    }
+    svg += "</defs>\n";
+    svg
+}
+
+pub(crate) fn board_squares(perspective: Color) -> String {
+    let mut svg = String::new();
+    for y in 0..8 {
+        for x in 0..8 {
+            let (left, top) = square_origin(square_from_xy(x, y), perspective);
+            let fill = if (x + y) % 2 == 0 { DARK_SQUARE_COLOR } else { LIGHT_SQUARE_COLOR };
+            svg += format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", left, top, PIECE_SIZE, PIECE_SIZE, fill).as_str();
+        }
+    }
+    svg
+}
+
+pub(crate) fn square_highlights(highlights: &[(u64, String)], perspective: Color) -> String {
+    let mut svg = String::new();
+    for (bitboard, color) in highlights {
+        for square in squares(*bitboard) {
+            let (left, top) = square_origin(square, perspective);
+            svg += format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"0.6\"/>\n",
+                left, top, PIECE_SIZE, PIECE_SIZE, color
+            ).as_str();
+        }
+    }
+    svg
+}
+
+pub(crate) fn board_pieces(chess_board: &ChessBoard, perspective: Color) -> String {
+    let mut svg = String::new();
+    for square in squares(chess_board.occupancy()) {
+        let (piece_type, color) = chess_board.piece_at(square).unwrap();
+        let (left, top) = square_origin(square, perspective);
+        svg += format!("<use xlink:href=\"#{}\" x=\"{}\" y=\"{}\"/>\n", piece_id(piece_type, color), left, top).as_str();
+    }
+    svg
+}
+
+/// Top left corner of a square in board units, with `PIECE_SIZE` units per square.
+pub(crate) fn square_origin(square: u64, perspective: Color) -> (f32, f32) {
+    let (x, y) = square_to_xy(square);
+    let (column, row) = match perspective {
+        Color::White => (x, 7 - y),
+        Color::Black => (7 - x, y),
+    };
+    (column as f32 * PIECE_SIZE, row as f32 * PIECE_SIZE)
+}
+
+pub(crate) fn square_center(square: u64, perspective: Color) -> (f32, f32) {
+    let (left, top) = square_origin(square, perspective);
+    (left + PIECE_SIZE / 2.0, top + PIECE_SIZE / 2.0)
+}
+
+/// Outline of an arrow from one point to another, with the tip at `to`.
+pub(crate) fn arrow_polygon(from: (f32, f32), to: (f32, f32)) -> Vec<(f32, f32)> {
+    const SHAFT_WIDTH: f32 = 9.0;
+    const HEAD_WIDTH: f32 = 22.0;
+    const HEAD_LENGTH: f32 = 18.0;
+
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return Vec::new();
+    }
+    let (ux, uy) = (dx / length, dy / length);
+    // Normal to the direction of the arrow
+    let (nx, ny) = (-uy, ux);
+    let base = (to.0 - ux * HEAD_LENGTH.min(length), to.1 - uy * HEAD_LENGTH.min(length));
+    let offset = |point: (f32, f32), width: f32| (point.0 + nx * width / 2.0, point.1 + ny * width / 2.0);
+
+    vec![
+        offset(from, SHAFT_WIDTH),
+        offset(base, SHAFT_WIDTH),
+        offset(base, HEAD_WIDTH),
+        to,
+        offset(base, -HEAD_WIDTH),
+        offset(base, -SHAFT_WIDTH),
+        offset(from, -SHAFT_WIDTH),
+    ]
+}
+
+pub(crate) fn piece_colors(color: Color) -> (&'static str, &'static str) {
+    // Fill and outline
+    match color {
+        Color::White => ("#ffffff", "#000000"),
+        Color::Black => ("#000000", "#000000"),
+    }
+}
+
+fn piece_id(piece_type: PieceType, color: Color) -> String {
+    let color = if color == Color::White { "white" } else { "black" };
+    format!("{}-{}", color, format!("{:?}", piece_type).to_lowercase())
+}
+
+fn piece_definition(piece_type: PieceType, color: Color) -> String {
+    let (fill, stroke) = piece_colors(color);
+    let mut definition = format!(
+        "<g id=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"1.5\" stroke-linejoin=\"round\">",
+        piece_id(piece_type, color), fill, stroke
+    );
+    for shape in piece_shapes(piece_type) {
+        definition += match shape {
+            Shape::Polygon(vertices) => format!("<polygon points=\"{}\"/>", points(vertices)),
+            Shape::Circle((cx, cy), r) => format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>", cx, cy, r),
+        }.as_str();
+    }
+    definition += "</g>\n";
+    definition
+}
+
+pub(crate) fn coordinates(perspective: Color) -> String {
+    let mut text = String::new();
+    let style = "font-family=\"sans-serif\" font-size=\"12\" fill=\"#555555\" text-anchor=\"middle\"";
+    for index in 0..8 {
+        let (x, y) = square_center(square_from_xy(index, index), perspective);
+        let file = (b'a' + index as u8) as char;
+        text += format!("<text x=\"{}\" y=\"{}\" {}>{}</text>\n", x, 8.0 * PIECE_SIZE + 14.0, style, file).as_str();
+        text += format!("<text x=\"{}\" y=\"{}\" {}>{}</text>\n", -MARGIN / 2.0, y + 4.0, style, index + 1).as_str();
+    }
+    text
+}
+
+fn points(vertices: &[(f32, f32)]) -> String {
+    vertices
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::board_utils::parse_square;
+    use crate::utils::fen_reader::read_fen;
+
+    #[test]
+    fn test_render_svg_start_position() {
+        let svg = render_svg(&ChessBoard::new(false), &SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(32, svg.matches("<use ").count());
+        assert_eq!(64, svg.matches("<rect ").count());
+        assert_eq!(16, svg.matches("<text ").count());
+        // The white king on e1 is in the fifth column of the bottom row
+        assert!(svg.contains("<use xlink:href=\"#white-king\" x=\"180\" y=\"315\"/>"));
+    }
+
+    #[test]
+    fn test_render_svg_black_perspective() {
+        let chess_board = read_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        let options = SvgOptions { perspective: Color::Black, coordinates: false, ..SvgOptions::default() };
+        let svg = render_svg(&chess_board, &options);
+        assert!(svg.contains("viewBox=\"0 0 360 360\""));
+        assert!(svg.contains("<use xlink:href=\"#white-king\" x=\"135\" y=\"0\"/>"));
+        assert!(svg.contains("<use xlink:href=\"#black-king\" x=\"135\" y=\"315\"/>"));
+        assert!(!svg.contains("<text "));
+    }
+
+    #[test]
+    fn test_render_svg_highlights_and_arrows() {
+        let e2 = parse_square("e2").unwrap();
+        let e4 = parse_square("e4").unwrap();
+        let options = SvgOptions {
+            highlights: vec![(e2 | e4, HIGHLIGHT_COLOR.to_string())],
+            arrows: vec![Arrow::new(e2, e4)],
+            ..SvgOptions::default()
+        };
+        let svg = render_svg(&ChessBoard::new(false), &options);
+        assert_eq!(2, svg.matches(format!("fill=\"{}\" fill-opacity=\"0.6\"", HIGHLIGHT_COLOR).as_str()).count());
+        assert!(svg.contains("<rect x=\"180\" y=\"180\" width=\"45\" height=\"45\" fill=\"#cdd26a\""));
+        assert_eq!(1, svg.matches(format!("fill=\"{}\" fill-opacity=\"0.8\"", ARROW_COLOR).as_str()).count());
+    }
+
+    #[test]
+    fn test_arrow_polygon() {
+        let arrow = arrow_polygon((0.0, 0.0), (0.0, -90.0));
+        assert_eq!(7, arrow.len());
+        assert_eq!((0.0, -90.0), arrow[3]);
+        // The head is wider than the shaft
+        assert!(arrow[2].0.abs() > arrow[1].0.abs());
+        assert!(arrow_polygon((10.0, 10.0), (10.0, 10.0)).is_empty());
+    }
+}
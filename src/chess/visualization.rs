@@ -3,45 +3,211 @@ use std::collections::HashMap;
 use super::chess_board::ChessBoard;
 use super::chess_board::get_square;
 use super::piece::{PieceType, Color};
+use crate::utils::board_utils::square_from_xy;
+use crate::utils::fen_writer::{castling_rights, write_fen};
 
 pub trait Visualize {
-    fn visualize(&self) -> String;
+    fn visualize(&self) -> String {
+        self.visualize_with(&RenderOptions::default())
+    }
+
+    fn visualize_with(&self, options: &RenderOptions) -> String;
+}
+
+/// Options for `Visualize::visualize_with`. The default gives the plain Unicode board seen from White.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    // The side shown at the bottom of the board
+    pub perspective: Color,
+    // Use FEN letters and '.' instead of the chess glyphs, for terminals without them
+    pub ascii: bool,
+    // Draw light and dark squares with ANSI background colors
+    pub colors: bool,
+    // Highlight the from and to squares of the last move in the move history
+    pub highlight_last_move: bool,
+    // Highlight a king in check and the pieces giving check
+    pub highlight_check: bool,
+    // Show the FEN, side to move and castling rights next to the board
+    pub side_panel: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            perspective: Color::White,
+            ascii: false,
+            colors: false,
+            highlight_last_move: false,
+            highlight_check: false,
+            side_panel: false,
+        }
+    }
 }
 
+// ANSI escape codes for the square backgrounds
+const ANSI_LIGHT_SQUARE: &str = "\x1b[48;5;223m";
+const ANSI_DARK_SQUARE: &str = "\x1b[48;5;137m";
+const ANSI_LAST_MOVE: &str = "\x1b[48;5;143m";
+const ANSI_CHECK: &str = "\x1b[48;5;167m";
+const ANSI_RESET: &str = "\x1b[0m";
+
 impl Visualize for ChessBoard {
 
-    fn visualize(& self) -> String {
+    fn visualize_with(&self, options: &RenderOptions) -> String {
+        let last_move = if options.highlight_last_move { last_move_squares(self) } else { 0 };
+        let check = if options.highlight_check { check_squares(self) } else { 0 };
+        let panel = if options.side_panel { side_panel(self) } else { Vec::new() };
+
+        // Rank 8 is at the top when seen from White, rank 1 when seen from Black
+        let ranks: Vec<i32> = if options.perspective == Color::White { (0..8).rev().collect() } else { (0..8).collect() };
+        let files: Vec<i32> = if options.perspective == Color::White { (0..8).collect() } else { (0..8).rev().collect() };
+
         let mut result = String::new();
-        for y in (0..8).rev() {
-            for x in 0..8 {
-                if x == 0 {
-                    result += format!("{} ", y + 1).as_str();
+        for (row, &y) in ranks.iter().enumerate() {
+            result += format!("{} ", y + 1).as_str();
+            let highlighted: Vec<bool> = files.iter().map(|&x| (last_move | check) & square_from_xy(x as usize, y as usize) != 0).collect();
+            for (column, &x) in files.iter().enumerate() {
+                let square = square_from_xy(x as usize, y as usize);
+                let character = if options.ascii { get_square_letter(self, x, y) } else { get_square_character(self, x, y) };
+                if options.colors {
+                    let background = if check & square != 0 {
+                        ANSI_CHECK
+                    } else if last_move & square != 0 {
+                        ANSI_LAST_MOVE
+                    } else if (x + y) % 2 == 0 {
+                        ANSI_DARK_SQUARE
+                    } else {
+                        ANSI_LIGHT_SQUARE
+                    };
+                    // The background shows the square color, so empty squares stay blank
+                    let character = if self.piece_at(square).is_none() { " " } else { character };
+                    result += format!("{} {} {}", background, character, ANSI_RESET).as_str();
+                } else {
+                    // Without colors highlighted squares are put between brackets
+                    let separator = match (highlighted[column], column + 1 < 8 && highlighted[column + 1]) {
+                        (true, true) => '|',
+                        (true, false) => ']',
+                        (false, true) => '[',
+                        (false, false) => ' ',
+                    };
+                    if column == 0 && highlighted[0] {
+                        result.pop();
+                        result.push('[');
+                    }
+                    result += format!("{}{}", character, separator).as_str();
                 }
-                result += format!("{} ", get_square_character(self, x, y)).as_str();
+            }
+            if let Some(line) = panel.get(row) {
+                result += format!("  {}", line).as_str();
             }
             result += "\n";
         }
-        
-        result += "  A B C D E F G H ";
+
+        let labels: Vec<String> = files.iter().map(|&x| ((b'A' + x as u8) as char).to_string()).collect();
+        if options.colors {
+            result += format!("   {} ", labels.join("  ")).as_str();
+        } else {
+            result += format!("  {} ", labels.join(" ")).as_str();
+        }
         result
     }
 }
 
+/// The from and to squares of the last move played on the board.
+pub(crate) fn last_move_squares(chess_board: &ChessBoard) -> u64 {
+    chess_board.move_history.last().map_or(0, |last_move| last_move.old_position | last_move.new_position)
+}
+
+/// The king of the side to move and the pieces giving check, empty when not in check.
+pub(crate) fn check_squares(chess_board: &ChessBoard) -> u64 {
+    if chess_board.is_in_check() {
+        chess_board.king_square(chess_board.active_color) | chess_board.checkers()
+    } else {
+        0
+    }
+}
+
+fn side_panel(chess_board: &ChessBoard) -> Vec<String> {
+    let to_move = if chess_board.active_color == Color::White { "White" } else { "Black" };
+    vec![
+        format!("FEN: {}", write_fen(chess_board)),
+        format!("To move: {}", to_move),
+        format!("Castling: {}", castling_rights(chess_board)),
+        format!("Move: {}", chess_board.fullmove_number),
+    ]
+}
+
+// The FEN letter of the piece on the square, or '.' for an empty square
+fn get_square_letter(chess_board: &ChessBoard, x: i32, y: i32) -> &'static str {
+    const WHITE_LETTERS: [&str; 6] = ["P", "N", "B", "R", "Q", "K"];
+    const BLACK_LETTERS: [&str; 6] = ["p", "n", "b", "r", "q", "k"];
+    match chess_board.piece_at(square_from_xy(x as usize, y as usize)) {
+        Some((piece_type, color)) => {
+            let index = PieceType::ALL.iter().position(|&other| other == piece_type).unwrap();
+            if color == Color::White { WHITE_LETTERS[index] } else { BLACK_LETTERS[index] }
+        }
+        None => ".",
+    }
+}
+
+/// Draws a bitboard as an 8x8 grid with rank 8 at the top, `x` for set bits and `.` for the others.
+/// The picture can be read back with `read_bitboard`.
+pub fn visualize_bitboard(bitboard: u64) -> String {
+    let mut result = String::new();
+    for y in (0..8).rev() {
+        result += format!("{} ", y + 1).as_str();
+        for x in 0..8 {
+            let marker = if bitboard & square_from_xy(x, y) != 0 { 'x' } else { '.' };
+            result += format!("{} ", marker).as_str();
+        }
+        result += "\n";
+    }
+    result += "  A B C D E F G H ";
+    result
+}
+
+/// Draws the board with each bitboard in `overlays` marked by its own character, e.g.
+/// `[(attacks, '*'), (BLOCKERMASKS_ROOK[square], '+')]`. A marked square shows the marker right after
+/// the piece or empty square, when bitboards overlap the first one in the list wins.
+pub fn visualize_overlay(chess_board: &ChessBoard, overlays: &[(u64, char)]) -> String {
+    let mut result = String::new();
+    for y in (0..8).rev() {
+        result += format!("{} ", y + 1).as_str();
+        for x in 0..8 {
+            let square = square_from_xy(x as usize, y as usize);
+            let marker = overlays
+                .iter()
+                .find(|(bitboard, _)| bitboard & square != 0)
+                .map_or(' ', |&(_, marker)| marker);
+            result += format!("{}{} ", get_square_character(chess_board, x, y), marker).as_str();
+        }
+        result += "\n";
+    }
+    result += "  A  B  C  D  E  F  G  H ";
+    result
+}
+
+pub(crate) const PIECE_CHARACTERS: [((PieceType, Color), &str); 12] = [
+    ((PieceType::Pawn, Color::White), "♙"),
+    ((PieceType::Pawn, Color::Black), "♟︎"),
+    ((PieceType::Rook, Color::White), "♖"),
+    ((PieceType::Rook, Color::Black), "♜"),
+    ((PieceType::Knight, Color::White), "♘"),
+    ((PieceType::Knight, Color::Black), "♞"),
+    ((PieceType::Bishop, Color::White), "♗"),
+    ((PieceType::Bishop, Color::Black), "♝"),
+    ((PieceType::Queen, Color::White), "♕"),
+    ((PieceType::Queen, Color::Black), "♛"),
+    ((PieceType::King, Color::White), "♔"),
+    ((PieceType::King, Color::Black), "♚"),
+];
+
+// Characters used for empty dark and light squares
+pub(crate) const DARK_SQUARE: &str = "■";
+pub(crate) const LIGHT_SQUARE: &str = "□";
+
 fn get_square_character(bitboard: &ChessBoard, x: i32, y: i32) -> &'static str {
-    let piece_characters: HashMap<(PieceType, Color), &'static str> = [
-        ((PieceType::Pawn, Color::White), "♙"),
-        ((PieceType::Pawn, Color::Black), "♟︎"),
-        ((PieceType::Rook, Color::White), "♖"),
-        ((PieceType::Rook, Color::Black), "♜"),
-        ((PieceType::Knight, Color::White), "♘"),
-        ((PieceType::Knight, Color::Black), "♞"),
-        ((PieceType::Bishop, Color::White), "♗"),
-        ((PieceType::Bishop, Color::Black), "♝"),
-        ((PieceType::Queen, Color::White), "♕"),
-        ((PieceType::Queen, Color::Black), "♛"),
-        ((PieceType::King, Color::White), "♔"),
-        ((PieceType::King, Color::Black), "♚"),
-    ].iter().cloned().collect();
+    let piece_characters: HashMap<(PieceType, Color), &'static str> = PIECE_CHARACTERS.iter().cloned().collect();
 
     for ((piece_type, color), character) in piece_characters {
         let positions = bitboard.get_piece_info(piece_type, color).positions;
@@ -51,20 +217,23 @@ fn get_square_character(bitboard: &ChessBoard, x: i32, y: i32) -> &'static str {
     }
 
     if (x + y) % 2 == 0 {
-        return "■";
+        return DARK_SQUARE;
     }
     
-    "□"
+    LIGHT_SQUARE
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chess::chess_move::Move;
+    use crate::utils::diagram_reader::read_bitboard;
+    use crate::utils::fen_reader::read_fen;
 
     #[test]
     fn test_visualize(){
-        let mut chessboard = ChessBoard::new(false);
+        let chessboard = ChessBoard::new(false);
         let result = chessboard.visualize().replace(" ", "");
         
         let expected = "
@@ -81,4 +250,98 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_visualize_default_options(){
+        let chessboard = ChessBoard::new(false);
+        assert_eq!(chessboard.visualize(), chessboard.visualize_with(&RenderOptions::default()));
+    }
+
+    #[test]
+    fn test_visualize_ascii_black_perspective(){
+        let chessboard = read_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        let options = RenderOptions { perspective: Color::Black, ascii: true, ..RenderOptions::default() };
+        let expected = "\
+1 R N B K Q B N R 
+2 P P P . P P P P 
+3 . . . . . . . . 
+4 . . . P . . . . 
+5 . . . . . . . . 
+6 . . . . . . . . 
+7 p p p p p p p p 
+8 r n b k q b n r 
+  H G F E D C B A ";
+        assert_eq!(expected, chessboard.visualize_with(&options));
+    }
+
+    #[test]
+    fn test_visualize_highlights_without_colors(){
+        let mut chessboard = ChessBoard::new(false);
+        let e2 = square_from_xy(4, 1);
+        let e4 = square_from_xy(4, 3);
+        chessboard.make_move(&Move::new(PieceType::Pawn, e2, e4));
+        let options = RenderOptions { ascii: true, highlight_last_move: true, ..RenderOptions::default() };
+        let result = chessboard.visualize_with(&options);
+        assert!(result.contains("4 . . . .[P]. . . \n"));
+        assert!(result.contains("2 P P P P[.]P P P \n"));
+
+        // A king in check is highlighted together with the checking piece, also on the edge of the board
+        let chessboard = read_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1");
+        let options = RenderOptions { ascii: true, highlight_check: true, ..RenderOptions::default() };
+        assert!(chessboard.visualize_with(&options).contains("1[r]. . .[K]. . . \n"));
+    }
+
+    #[test]
+    fn test_visualize_colors(){
+        let chessboard = read_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1");
+        let options = RenderOptions { colors: true, highlight_check: true, ..RenderOptions::default() };
+        let result = chessboard.visualize_with(&options);
+        let first_rank = result.lines().nth(7).unwrap();
+        assert!(first_rank.starts_with(&format!("1 {} ♜ {}", ANSI_CHECK, ANSI_RESET)));
+        assert!(first_rank.contains(&format!("{}   {}", ANSI_LIGHT_SQUARE, ANSI_RESET)));
+        assert!(result.ends_with("   A  B  C  D  E  F  G  H "));
+    }
+
+    #[test]
+    fn test_visualize_side_panel(){
+        let chessboard = ChessBoard::new(false);
+        let options = RenderOptions { side_panel: true, ..RenderOptions::default() };
+        let result = chessboard.visualize_with(&options);
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines[0].ends_with("  FEN: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+        assert!(lines[1].ends_with("  To move: White"));
+        assert!(lines[2].ends_with("  Castling: KQkq"));
+    }
+
+    #[test]
+    fn test_visualize_bitboard(){
+        let expected = "\
+8 x . . . . . . . 
+7 . . . . . . . . 
+6 . . . . . . . . 
+5 . . . . . . . . 
+4 . . . . . . . . 
+3 . . . . . . . . 
+2 . . . . . . . . 
+1 . . . . . . . x 
+  A B C D E F G H ";
+        let bitboard = 0b10000000_00000000_00000000_00000000_00000000_00000000_00000000_00000001;
+        assert_eq!(expected, visualize_bitboard(bitboard));
+        assert_eq!(bitboard, read_bitboard(&visualize_bitboard(bitboard)));
+        assert_eq!(0, read_bitboard(&visualize_bitboard(0)));
+    }
+
+    #[test]
+    fn test_visualize_overlay(){
+        let chessboard = read_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        let a1 = square_from_xy(0, 0);
+        let attacks = crate::movesets::move_generator::piece_targets(PieceType::Rook, a1, chessboard.occupancy());
+        let mask = crate::movesets::magic_bitboards::BLOCKERMASKS_ROOK[a1.trailing_zeros() as usize];
+        let result = visualize_overlay(&chessboard, &[(attacks, '*'), (mask, '+')]);
+        let lines: Vec<&str> = result.lines().collect();
+        // The blocker mask only shows where the rook attacks don't reach
+        assert_eq!("1 ♖  □* ■* □* ♔* □+ ■+ □  ", lines[7]);
+        assert_eq!("8 □* ■  □  ■  ♚  ■  □  ■  ", lines[0]);
+        assert_eq!("  A  B  C  D  E  F  G  H ", lines[8]);
+    }
+
 }
\ No newline at end of file
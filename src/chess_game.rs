@@ -1,71 +1,532 @@
-/// This class is mostly used for practicing the Rust language. 
-/// It is currently unused
-
-#[derive(Debug, Copy, Clone)]enum PieceType {
-    Pawn,
-    Knight,
-    Bishop,
-    Rook,
-    Queen,
-    King,
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::Move;
+use crate::chess::piece::{Color, PieceType};
+use crate::utils::fen_writer::write_fen;
+
+// A deliberately simple 8x8 mailbox board with its own move generator. It is written to be obviously
+// correct rather than fast and shares no code with the bitboard move generation, so playing the same
+// games on both boards and comparing the moves finds bugs in the bitboard generators.
+
+const KNIGHT_STEPS: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_STEPS: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const ROOK_DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+const PROMOTION_PIECES: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+const BACK_RANK: [PieceType; 8] = [
+    PieceType::Rook,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Queen,
+    PieceType::King,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Rook,
+];
+
+/// A square as (rank, file), (0, 0) is a1 and (7, 7) is h8.
+pub type Square = (usize, usize);
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Piece {
+    pub piece_type: PieceType,
+    pub color: Color,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct MailboxMove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceType>,
 }
-#[derive(Debug, Copy, Clone)]
-enum Color{
-    Black,
-    White
+
+impl MailboxMove {
+    pub fn to_uci(&self) -> String {
+        let mut result = square_name(self.from) + &square_name(self.to);
+        if let Some(promotion) = self.promotion {
+            result.push(promotion.letter().to_ascii_lowercase());
+        }
+        result
+    }
 }
-#[derive(Debug, Copy, Clone)]
-struct Piece {
-    piece_type: PieceType,
-    color: Color,
-    value: i16,
-    position: (u8, u8)
+
+/// The board is indexed as `board[rank][file]`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct ChessGame {
+    pub board: [[Option<Piece>; 8]; 8],
+    pub active_color: Color,
+    // White king side, white queen side, black king side, black queen side
+    pub castling: [bool; 4],
+    pub en_passant: Option<Square>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
 }
 
-type ChessBoard = [[Option<Piece>; 8]; 8];
+impl ChessGame {
+    /// Returns a game with the standard starting position.
+    pub fn new() -> ChessGame {
+        let mut board = [[None; 8]; 8];
+        for file in 0..8 {
+            board[0][file] = Some(Piece { piece_type: BACK_RANK[file], color: Color::White });
+            board[1][file] = Some(Piece { piece_type: PieceType::Pawn, color: Color::White });
+            board[6][file] = Some(Piece { piece_type: PieceType::Pawn, color: Color::Black });
+            board[7][file] = Some(Piece { piece_type: BACK_RANK[file], color: Color::Black });
+        }
+        ChessGame { board, active_color: Color::White, castling: [true; 4], en_passant: None, halfmove_clock: 0, fullmove_number: 1 }
+    }
+
+    /// Reads a position from FEN, the move clocks may be left out.
+    pub fn from_fen(fen: &str) -> Result<ChessGame, String> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if !(4..=6).contains(&fields.len()) {
+            return Err(format!("Expected 4 to 6 fields in FEN {}", fen));
+        }
+
+        let mut board = [[None; 8]; 8];
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(format!("Expected 8 ranks in FEN {}", fen));
+        }
+        for (index, rank_text) in ranks.iter().enumerate() {
+            let rank = 7 - index;
+            let mut file = 0;
+            for character in rank_text.chars() {
+                if let Some(empty) = character.to_digit(10) {
+                    file += empty as usize;
+                    continue;
+                }
+                let piece_type = PieceType::from_letter(character).ok_or(format!("Unknown piece {} in FEN {}", character, fen))?;
+                let color = if character.is_ascii_uppercase() { Color::White } else { Color::Black };
+                if file >= 8 {
+                    return Err(format!("Rank {} is too long in FEN {}", rank + 1, fen));
+                }
+                board[rank][file] = Some(Piece { piece_type, color });
+                file += 1;
+            }
+            if file != 8 {
+                return Err(format!("Rank {} does not have 8 squares in FEN {}", rank + 1, fen));
+            }
+        }
+
+        let active_color = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(format!("Unknown color {} in FEN {}", fields[1], fen)),
+        };
+        let castling = ['K', 'Q', 'k', 'q'].map(|right| fields[2].contains(right));
+        let en_passant = match fields[3] {
+            "-" => None,
+            name => Some(parse_square_name(name).ok_or(format!("Unknown en passant square {} in FEN {}", name, fen))?),
+        };
+        let number = |index: usize, default: u32| match fields.get(index) {
+            Some(text) => text.parse().map_err(|_| format!("Invalid number {} in FEN {}", text, fen)),
+            None => Ok(default),
+        };
+        Ok(ChessGame { board, active_color, castling, en_passant, halfmove_clock: number(4, 0)?, fullmove_number: number(5, 1)? })
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut ranks = Vec::new();
+        for rank in (0..8).rev() {
+            let mut text = String::new();
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.board[rank][file] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            text += &empty.to_string();
+                            empty = 0;
+                        }
+                        let letter = piece.piece_type.letter();
+                        text.push(if piece.color == Color::White { letter } else { letter.to_ascii_lowercase() });
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                text += &empty.to_string();
+            }
+            ranks.push(text);
+        }
+        let color = if self.active_color == Color::White { "w" } else { "b" };
+        let castling: String = ['K', 'Q', 'k', 'q'].iter().zip(self.castling).filter(|(_, allowed)| *allowed).map(|(right, _)| *right).collect();
+        let castling = if castling.is_empty() { "-".to_string() } else { castling };
+        let en_passant = self.en_passant.map_or("-".to_string(), square_name);
+        format!("{} {} {} {} {} {}", ranks.join("/"), color, castling, en_passant, self.halfmove_clock, self.fullmove_number)
+    }
+
+    /// All legal moves for the active color: the moves that follow the rules of the pieces and do
+    /// not leave the own king attacked.
+    pub fn legal_moves(&self) -> Vec<MailboxMove> {
+        self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|chess_move| {
+                let mut next = *self;
+                next.make_move(chess_move);
+                match next.king_square(self.active_color) {
+                    Some(king) => !next.is_attacked(king, !self.active_color),
+                    None => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Plays a move, which is assumed to be legal.
+    pub fn make_move(&mut self, chess_move: &MailboxMove) {
+        let (from, to) = (chess_move.from, chess_move.to);
+        let piece = self.board[from.0][from.1].expect("There is no piece to move");
+        let mut is_capture = self.board[to.0][to.1].is_some();
+
+        // En passant takes the pawn next to the moving pawn
+        if piece.piece_type == PieceType::Pawn && Some(to) == self.en_passant && !is_capture {
+            self.board[from.0][to.1] = None;
+            is_capture = true;
+        }
+        // Castling also moves the rook next to the king
+        if piece.piece_type == PieceType::King && from.1 == 4 && (to.1 == 6 || to.1 == 2) {
+            let (rook_from, rook_to) = if to.1 == 6 { (7, 5) } else { (0, 3) };
+            self.board[from.0][rook_to] = self.board[from.0][rook_from].take();
+        }
+
+        self.board[from.0][from.1] = None;
+        self.board[to.0][to.1] = Some(Piece { piece_type: chess_move.promotion.unwrap_or(piece.piece_type), color: piece.color });
+
+        // Moving from or to the starting square of a king or rook loses the castling rights
+        for square in [from, to] {
+            match square {
+                (0, 4) => self.castling[..2].fill(false),
+                (0, 7) => self.castling[0] = false,
+                (0, 0) => self.castling[1] = false,
+                (7, 4) => self.castling[2..].fill(false),
+                (7, 7) => self.castling[2] = false,
+                (7, 0) => self.castling[3] = false,
+                _ => {}
+            }
+        }
+
+        self.en_passant = None;
+        if piece.piece_type == PieceType::Pawn && from.0.abs_diff(to.0) == 2 {
+            self.en_passant = Some(((from.0 + to.0) / 2, from.1));
+        }
+        if piece.piece_type == PieceType::Pawn || is_capture {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.active_color == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.active_color = !self.active_color;
+    }
 
+    /// Returns true if any piece of color `by` attacks the square.
+    pub fn is_attacked(&self, square: Square, by: Color) -> bool {
+        self.pieces(by).into_iter().any(|(from, piece)| match piece.piece_type {
+            PieceType::Pawn => {
+                let forward = if by == Color::White { 1 } else { -1 };
+                [-1, 1].iter().any(|&side| offset(from, (forward, side)) == Some(square))
+            }
+            PieceType::Knight => KNIGHT_STEPS.iter().any(|&step| offset(from, step) == Some(square)),
+            PieceType::King => KING_STEPS.iter().any(|&step| offset(from, step) == Some(square)),
+            PieceType::Bishop => BISHOP_DIRECTIONS.iter().any(|&direction| self.ray(from, direction).contains(&square)),
+            PieceType::Rook => ROOK_DIRECTIONS.iter().any(|&direction| self.ray(from, direction).contains(&square)),
+            PieceType::Queen => KING_STEPS.iter().any(|&direction| self.ray(from, direction).contains(&square)),
+        })
+    }
 
-#[derive(Debug, Copy, Clone)]
-pub(crate) struct ChessGame {
-    board: ChessBoard
+    pub fn is_in_check(&self) -> bool {
+        self.king_square(self.active_color).is_some_and(|king| self.is_attacked(king, !self.active_color))
+    }
+
+    fn pseudo_legal_moves(&self) -> Vec<MailboxMove> {
+        let color = self.active_color;
+        let mut moves = Vec::new();
+        for (from, piece) in self.pieces(color) {
+            let mut targets = Vec::new();
+            match piece.piece_type {
+                PieceType::Pawn => {
+                    self.add_pawn_moves(from, &mut moves);
+                    continue;
+                }
+                PieceType::Knight => targets.extend(KNIGHT_STEPS.iter().filter_map(|&step| offset(from, step))),
+                PieceType::King => targets.extend(KING_STEPS.iter().filter_map(|&step| offset(from, step))),
+                PieceType::Bishop => BISHOP_DIRECTIONS.iter().for_each(|&direction| targets.extend(self.ray(from, direction))),
+                PieceType::Rook => ROOK_DIRECTIONS.iter().for_each(|&direction| targets.extend(self.ray(from, direction))),
+                PieceType::Queen => KING_STEPS.iter().for_each(|&direction| targets.extend(self.ray(from, direction))),
+            }
+            for to in targets {
+                if self.board[to.0][to.1].is_none_or(|target| target.color != color) {
+                    moves.push(MailboxMove { from, to, promotion: None });
+                }
+            }
+        }
+        self.add_castling_moves(&mut moves);
+        moves
+    }
+
+    fn add_pawn_moves(&self, from: Square, moves: &mut Vec<MailboxMove>) {
+        let color = self.active_color;
+        let (forward, start_rank, last_rank) = if color == Color::White { (1, 1, 7) } else { (-1, 6, 0) };
+        let mut targets = Vec::new();
+
+        if let Some(one_step) = offset(from, (forward, 0)).filter(|to| self.is_empty(*to)) {
+            targets.push(one_step);
+            if let Some(two_steps) = offset(from, (2 * forward, 0)).filter(|to| from.0 == start_rank && self.is_empty(*to)) {
+                targets.push(two_steps);
+            }
+        }
+        for side in [-1, 1] {
+            if let Some(to) = offset(from, (forward, side)) {
+                let takes_piece = self.board[to.0][to.1].is_some_and(|target| target.color != color);
+                if takes_piece || self.en_passant == Some(to) {
+                    targets.push(to);
+                }
+            }
+        }
+
+        for to in targets {
+            if to.0 == last_rank {
+                moves.extend(PROMOTION_PIECES.iter().map(|&promotion| MailboxMove { from, to, promotion: Some(promotion) }));
+            } else {
+                moves.push(MailboxMove { from, to, promotion: None });
+            }
+        }
+    }
+
+    // The king may not castle out of, through or into check, and the squares between king and rook
+    // must be empty
+    fn add_castling_moves(&self, moves: &mut Vec<MailboxMove>) {
+        let color = self.active_color;
+        let (rank, rights) = if color == Color::White { (0, [self.castling[0], self.castling[1]]) } else { (7, [self.castling[2], self.castling[3]]) };
+        let own = |file: usize, piece_type: PieceType| self.board[rank][file] == Some(Piece { piece_type, color });
+        if !own(4, PieceType::King) || self.is_attacked((rank, 4), !color) {
+            return;
+        }
+        // King side and queen side: rook file, squares that must be empty, squares the king crosses
+        let sides: [(bool, usize, &[usize], [usize; 2]); 2] = [(rights[0], 7, &[5, 6], [5, 6]), (rights[1], 0, &[1, 2, 3], [3, 2])];
+        for (allowed, rook_file, empty, crossed) in sides {
+            if allowed
+                && own(rook_file, PieceType::Rook)
+                && empty.iter().all(|&file| self.is_empty((rank, file)))
+                && crossed.iter().all(|&file| !self.is_attacked((rank, file), !color))
+            {
+                moves.push(MailboxMove { from: (rank, 4), to: (rank, crossed[1]), promotion: None });
+            }
+        }
+    }
+
+    // The squares a slider reaches in one direction, up to and including the first piece
+    fn ray(&self, from: Square, direction: (i32, i32)) -> Vec<Square> {
+        let mut squares = Vec::new();
+        let mut current = from;
+        while let Some(next) = offset(current, direction) {
+            squares.push(next);
+            if !self.is_empty(next) {
+                break;
+            }
+            current = next;
+        }
+        squares
+    }
+
+    fn pieces(&self, color: Color) -> Vec<(Square, Piece)> {
+        let mut pieces = Vec::new();
+        for rank in 0..8 {
+            for file in 0..8 {
+                if let Some(piece) = self.board[rank][file].filter(|piece| piece.color == color) {
+                    pieces.push(((rank, file), piece));
+                }
+            }
+        }
+        pieces
+    }
+
+    fn king_square(&self, color: Color) -> Option<Square> {
+        self.pieces(color).into_iter().find(|(_, piece)| piece.piece_type == PieceType::King).map(|(square, _)| square)
+    }
+
+    fn is_empty(&self, square: Square) -> bool {
+        self.board[square.0][square.1].is_none()
+    }
 }
 
-impl ChessGame{
-    #[doc = "This function return a new ChessGame with a standard chess board configuration."]
-    pub fn new() -> ChessGame {
-        let mut board: ChessBoard =  [[None;8]; 8];
-
-        // Initialize white pawns
-        for i in 0..8 {
-            board[1][i] = Some(Piece{piece_type: PieceType::Pawn,color: Color::White, value: 1, position: (1,i as u8)});
-        }
-
-        // Initialize white pieces
-        board[0][0] = Some(Piece{piece_type: PieceType::Rook,color: Color::White, value: 5, position: (0,0 as u8)});
-        board[0][1] = Some(Piece{piece_type: PieceType::Knight,color: Color::White, value: 3, position: (0,1 as u8)});
-        board[0][2] = Some(Piece{piece_type: PieceType::Bishop,color: Color::White, value: 3, position: (0,2 as u8)});
-        board[0][3] = Some(Piece{piece_type: PieceType::Queen,color: Color::White, value: 9, position: (0,3 as u8)});
-        board[0][4] = Some(Piece{piece_type: PieceType::King,color: Color::White, value: 200, position: (0,4 as u8)});
-        board[0][5] = Some(Piece{piece_type: PieceType::Bishop,color: Color::White, value: 3, position: (0,5 as u8)});
-        board[0][6] = Some(Piece{piece_type: PieceType::Knight,color: Color::White, value: 3, position: (0,6 as u8)});
-        board[0][7] = Some(Piece{piece_type: PieceType::Rook,color: Color::White, value: 5, position: (0,7 as u8)});
-
-        //Initialize black pawns
-        for i in 0..8 {
-            board[6][i] = Some(Piece{piece_type: PieceType::Pawn,color: Color::Black, value: 1, position: (6,i as u8)});
-        }
-
-        //Initialize black pieces
-        board[7][0] = Some(Piece{piece_type: PieceType::Rook,color: Color::Black, value: 5, position: (7,0 as u8)});
-        board[7][1] = Some(Piece{piece_type: PieceType::Knight,color: Color::Black, value: 3, position: (7,1 as u8)});
-        board[7][2] = Some(Piece{piece_type: PieceType::Bishop,color: Color::Black, value: 3, position: (7,2 as u8)});
-        board[7][3] = Some(Piece{piece_type: PieceType::Queen,color: Color::Black, value: 9, position: (7,3 as u8)});
-        board[7][4] = Some(Piece{piece_type: PieceType::King,color: Color::Black, value: 200, position: (7,4 as u8)});
-        board[7][5] = Some(Piece{piece_type: PieceType::Bishop,color: Color::Black, value: 3, position: (7,5 as u8)});
-        board[7][6] = Some(Piece{piece_type: PieceType::Knight,color: Color::Black, value: 3, position: (7,6 as u8)});
-        board[7][7] = Some(Piece{piece_type: PieceType::Rook,color: Color::Black, value: 5, position: (7,7 as u8)});
-        
-        ChessGame{board: board}
-
-    }
-}
\ No newline at end of file
+impl Default for ChessGame {
+    fn default() -> Self {
+        ChessGame::new()
+    }
+}
+
+fn offset(square: Square, (ranks, files): (i32, i32)) -> Option<Square> {
+    let rank = square.0 as i32 + ranks;
+    let file = square.1 as i32 + files;
+    if (0..8).contains(&rank) && (0..8).contains(&file) {
+        Some((rank as usize, file as usize))
+    } else {
+        None
+    }
+}
+
+fn square_name((rank, file): Square) -> String {
+    format!("{}{}", (b'a' + file as u8) as char, rank + 1)
+}
+
+fn parse_square_name(name: &str) -> Option<Square> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 2 || !(b'a'..=b'h').contains(&bytes[0]) || !(b'1'..=b'8').contains(&bytes[1]) {
+        return None;
+    }
+    Some(((bytes[1] - b'1') as usize, (bytes[0] - b'a') as usize))
+}
+
+/// A position where the bitboard move generation and the mailbox reference disagree.
+#[derive(PartialEq, Debug, Clone)]
+pub struct GeneratorMismatch {
+    pub fen: String,
+    // Legal moves in UCI that only the mailbox generator found
+    pub missing: Vec<String>,
+    // Moves in UCI that only the bitboard generator found
+    pub extra: Vec<String>,
+    // Moves in UCI that the bitboard generator lists more than once
+    pub duplicates: Vec<String>,
+    // A move after which the boards differ, with the FEN of the mailbox and of the bitboard board
+    pub different_position: Option<(String, String, String)>,
+}
+
+impl fmt::Display for GeneratorMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Move generators disagree in {}", self.fen)?;
+        if !self.missing.is_empty() {
+            write!(f, "\n  missing from the bitboard moves: {}", self.missing.join(" "))?;
+        }
+        if !self.extra.is_empty() {
+            write!(f, "\n  not legal according to the mailbox: {}", self.extra.join(" "))?;
+        }
+        if !self.duplicates.is_empty() {
+            write!(f, "\n  listed more than once by the bitboard: {}", self.duplicates.join(" "))?;
+        }
+        if let Some((chess_move, mailbox, bitboard)) = &self.different_position {
+            write!(f, "\n  after {} the mailbox has {} and the bitboard {}", chess_move, mailbox, bitboard)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the legal moves of the board with those of the mailbox reference in the same position,
+/// and the positions both reach after every move.
+pub fn compare_with_mailbox(chess_board: &ChessBoard) -> Result<(), Box<GeneratorMismatch>> {
+    let fen = write_fen(chess_board);
+    let game = ChessGame::from_fen(&fen).expect("FEN written by write_fen can be read");
+    let mailbox_moves = game.legal_moves();
+    let bitboard_moves = chess_board.legal_moves();
+    let mut expected: Vec<String> = mailbox_moves.iter().map(MailboxMove::to_uci).collect();
+    let mut actual: Vec<String> = bitboard_moves.iter().map(Move::to_uci).collect();
+    expected.sort();
+    actual.sort();
+
+    let missing: Vec<String> = expected.iter().filter(|uci| !actual.contains(uci)).cloned().collect();
+    let extra: Vec<String> = actual.iter().filter(|uci| !expected.contains(uci)).cloned().collect();
+    // Duplicates do not show up as missing or extra moves
+    let mut duplicates: Vec<String> = actual.windows(2).filter(|pair| pair[0] == pair[1]).map(|pair| pair[0].clone()).collect();
+    duplicates.dedup();
+    let different_position = mailbox_moves.iter().find_map(|mailbox_move| {
+        let uci = mailbox_move.to_uci();
+        let chess_move = bitboard_moves.iter().find(|chess_move| chess_move.to_uci() == uci)?;
+        let mut next_game = game;
+        next_game.make_move(mailbox_move);
+        let mut next_board = chess_board.clone();
+        next_board.make_move(chess_move);
+        let (mailbox_fen, bitboard_fen) = (next_game.to_fen(), write_fen(&next_board));
+        (mailbox_fen != bitboard_fen).then_some((uci, mailbox_fen, bitboard_fen))
+    });
+
+    if missing.is_empty() && extra.is_empty() && duplicates.is_empty() && different_position.is_none() {
+        Ok(())
+    } else {
+        Err(Box::new(GeneratorMismatch { fen, missing, extra, duplicates, different_position }))
+    }
+}
+
+/// Plays `games` random games of at most `plies` half moves from the given position and compares the
+/// bitboard board with the mailbox reference in every position on the way. Returns the number of
+/// positions compared, or the first mismatch.
+pub fn play_random_games(start: &ChessBoard, games: usize, plies: usize, seed: u64) -> Result<usize, Box<GeneratorMismatch>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut positions = 0;
+    for _ in 0..games {
+        let mut chess_board = start.clone();
+        for _ in 0..plies {
+            compare_with_mailbox(&chess_board)?;
+            positions += 1;
+            let legal_moves = chess_board.legal_moves();
+            if legal_moves.is_empty() {
+                break;
+            }
+            chess_board.make_move(&legal_moves[rng.gen_range(0..legal_moves.len())]);
+        }
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movesets::test_positions::KIWIPETE;
+    use crate::utils::fen_reader::read_fen;
+
+    #[test]
+    fn test_new_and_fen() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(fen, ChessGame::new().to_fen());
+        assert_eq!(ChessGame::new(), ChessGame::from_fen(fen).unwrap());
+        assert_eq!(KIWIPETE, ChessGame::from_fen(KIWIPETE).unwrap().to_fen());
+        assert!(ChessGame::from_fen("8/8/8 w - -").is_err());
+    }
+
+    #[test]
+    fn test_mailbox_legal_moves() {
+        assert_eq!(20, ChessGame::new().legal_moves().len());
+        let kiwipete = ChessGame::from_fen(KIWIPETE).unwrap();
+        assert_eq!(48, kiwipete.legal_moves().len());
+        let promotions = ChessGame::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1").unwrap();
+        assert_eq!(24, promotions.legal_moves().len());
+    }
+
+    #[test]
+    fn test_mailbox_make_move() {
+        let mut game = ChessGame::from_fen("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+        let en_passant = MailboxMove { from: (4, 4), to: (5, 3), promotion: None };
+        game.make_move(&en_passant);
+        assert_eq!("r3k2r/8/3P4/8/8/8/8/R3K2R b KQkq - 0 1", game.to_fen());
+        let castling = MailboxMove { from: (7, 4), to: (7, 2), promotion: None };
+        game.make_move(&castling);
+        assert_eq!("2kr3r/8/3P4/8/8/8/8/R3K2R w KQ - 1 2", game.to_fen());
+    }
+
+    #[test]
+    fn test_compare_with_mailbox() {
+        assert_eq!(Ok(()), compare_with_mailbox(&read_fen(KIWIPETE)));
+        let mismatch = GeneratorMismatch {
+            fen: "8/8/8/8/8/8/8/K6k w - - 0 1".to_string(),
+            missing: vec!["a1a2".to_string()],
+            extra: Vec::new(),
+            duplicates: Vec::new(),
+            different_position: None,
+        };
+        assert_eq!("Move generators disagree in 8/8/8/8/8/8/8/K6k w - - 0 1\n  missing from the bitboard moves: a1a2", mismatch.to_string());
+        let duplicated = GeneratorMismatch { missing: Vec::new(), duplicates: vec!["a1b2".to_string()], ..mismatch };
+        assert_eq!("Move generators disagree in 8/8/8/8/8/8/8/K6k w - - 0 1\n  listed more than once by the bitboard: a1b2", duplicated.to_string());
+    }
+
+    #[test]
+    fn test_play_random_games() {
+        // More games are played by the differential integration test
+        match play_random_games(&ChessBoard::new(false), 4, 40, 46) {
+            Ok(positions) => assert!(positions > 4),
+            Err(mismatch) => panic!("{}", mismatch),
+        }
+    }
+}
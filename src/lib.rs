@@ -0,0 +1,4 @@
+pub mod chess;
+pub mod movesets;
+pub mod utils;
+pub mod chess_game;
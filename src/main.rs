@@ -1,10 +1,7 @@
-mod chess;
-mod movesets;
-mod utils;
-use chess::chess_board::ChessBoard;
-use chess::visualization::Visualize;
+use super_rusty::chess::chess_board::ChessBoard;
+use super_rusty::chess::visualization::Visualize;
 
 fn main() {
     let chess_board : ChessBoard = ChessBoard::new(false);
     println!("{}", chess_board.visualize());
-}
\ No newline at end of file
+}
@@ -1,7 +1,22 @@
-mod knight;
-mod bishop;
-mod king;
-mod pawn;
-mod magic_bitboards;
-mod rook;
-mod queen;
\ No newline at end of file
+pub mod knight;
+pub mod bishop;
+pub mod king;
+pub mod pawn;
+pub mod magic_bitboards;
+mod magic_numbers;
+pub mod rook;
+pub mod queen;
+pub mod sliders;
+pub mod pext;
+pub mod kogge_stone;
+pub mod hyperbola_quintessence;
+pub mod attacks;
+pub mod move_generator;
+pub mod legality;
+pub mod move_validation;
+pub mod unmoves;
+pub mod move_list;
+pub mod move_picker;
+pub mod perft;
+#[cfg(test)]
+pub(crate) mod test_positions;
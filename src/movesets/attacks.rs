@@ -0,0 +1,173 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::{castling_rook_squares, Move};
+use crate::chess::piece::{Color, PieceType};
+use crate::movesets::bishop::bishop_move;
+use crate::movesets::king::all_king_moves;
+use crate::movesets::knight::all_knight_moves;
+use crate::movesets::move_generator::piece_targets;
+use crate::movesets::pawn::{black_pawn_attacks, white_pawn_attacks};
+use crate::movesets::rook::rook_move;
+use lazy_static::lazy_static;
+
+// Attack queries on a full board. All leaper and slider patterns are symmetric, so the pieces
+// attacking a square are found by generating the attacks of each piece type from that square
+// and intersecting them with the pieces of the attacking side.
+
+lazy_static! {
+    // The squares strictly between two squares on the same rank, file or diagonal, empty otherwise
+    static ref BETWEEN: Vec<[u64; 64]> = generate_between();
+}
+
+fn generate_between() -> Vec<[u64; 64]> {
+    let mut between = vec![[0; 64]; 64];
+    for (from, row) in between.iter_mut().enumerate() {
+        for (to, squares_between) in row.iter_mut().enumerate() {
+            let (from_square, to_square) = (1u64 << from, 1u64 << to);
+            // Sliding from both ends with the other end as only blocker meets in the squares between
+            if rook_move(from_square, 0) & to_square != 0 {
+                *squares_between = rook_move(from_square, to_square) & rook_move(to_square, from_square);
+            } else if bishop_move(from_square, 0) & to_square != 0 {
+                *squares_between = bishop_move(from_square, to_square) & bishop_move(to_square, from_square);
+            }
+        }
+    }
+    between
+}
+
+/// Returns the squares strictly between two squares on the same line, or 0 if they are not on a line.
+pub(crate) fn between(from: u64, to: u64) -> u64 {
+    BETWEEN[from.trailing_zeros() as usize][to.trailing_zeros() as usize]
+}
+
+/// Returns a bitboard with all pieces of color `by` that attack `square`, given the `occupancy`.
+pub(crate) fn attackers_to(chess_board: &ChessBoard, square: u64, by: Color, occupancy: u64) -> u64 {
+    let pieces = |piece_type: PieceType| chess_board.get_piece_info(piece_type, by).positions;
+    let rooks_queens = pieces(PieceType::Rook) | pieces(PieceType::Queen);
+    let bishops_queens = pieces(PieceType::Bishop) | pieces(PieceType::Queen);
+
+    // A white pawn attacks the square if a black pawn on the square would attack the white pawn
+    let pawn_attackers = match by {
+        Color::White => black_pawn_attacks(square, pieces(PieceType::Pawn)),
+        Color::Black => white_pawn_attacks(square, pieces(PieceType::Pawn)),
+    };
+
+    pawn_attackers
+        | (all_knight_moves(square) & pieces(PieceType::Knight))
+        | (all_king_moves(square) & pieces(PieceType::King))
+        | (rook_move(square, occupancy) & rooks_queens)
+        | (bishop_move(square, occupancy) & bishops_queens)
+}
+
+impl ChessBoard {
+    /// Returns true if any piece of color `by` attacks the given square.
+    pub fn is_square_attacked(&self, square: u64, by: Color) -> bool {
+        attackers_to(self, square, by, self.occupancy()) != 0
+    }
+
+    /// Returns a bitboard with the pieces giving check to the active color.
+    pub fn checkers(&self) -> u64 {
+        let king = self.king_square(self.active_color);
+        if king == 0 {
+            return 0;
+        }
+        attackers_to(self, king, !self.active_color, self.occupancy())
+    }
+
+    /// Returns true if the king of the active color is in check.
+    pub fn is_in_check(&self) -> bool {
+        self.checkers() != 0
+    }
+
+    /// Returns true if the pseudo-legal move puts the opponent in check, either with the moved piece
+    /// or by uncovering a slider. Castling checks with the rook, and en passant can uncover a slider
+    /// through the square of the captured pawn.
+    pub fn gives_check(&self, chess_move: &Move) -> bool {
+        let color = self.active_color;
+        let king = self.king_square(!color);
+        if king == 0 {
+            return false;
+        }
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        let mut occupancy = (self.occupancy() & !from) | to;
+        // Pieces that leave their square and so can not give a discovered check
+        let mut moved = from;
+
+        let direct = if chess_move.is_castling() {
+            let (rook_from, rook_to) = castling_rook_squares(to, from);
+            occupancy = (occupancy & !rook_from) | rook_to;
+            moved |= rook_from;
+            rook_move(rook_to, occupancy) & king
+        } else {
+            if chess_move.piece_type == PieceType::Pawn && to == self.en_passant {
+                occupancy &= !match color {
+                    Color::White => to >> 8,
+                    Color::Black => to << 8,
+                };
+            }
+            match chess_move.promotion.unwrap_or(chess_move.piece_type) {
+                PieceType::Pawn => match color {
+                    Color::White => white_pawn_attacks(to, king),
+                    Color::Black => black_pawn_attacks(to, king),
+                },
+                piece_type => piece_targets(piece_type, to, occupancy) & king,
+            }
+        };
+        let pieces = |piece_type: PieceType| self.get_piece_info(piece_type, color).positions;
+        let sliders = (pieces(PieceType::Rook) | pieces(PieceType::Bishop) | pieces(PieceType::Queen)) & !moved;
+        let discovered = attackers_to(self, king, color, occupancy) & sliders;
+        direct != 0 || discovered != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::board_utils::parse_square;
+    use crate::utils::fen_reader::read_fen;
+
+    #[test]
+    fn test_attackers_to_start_position() {
+        let chess_board = ChessBoard::new(false);
+        // f3 is covered by the e2 and g2 pawns and the g1 knight
+        let f3 = 0b00000000_00000000_00000000_00000000_00000000_00000100_00000000_00000000;
+        let expected = 0b00000000_00000000_00000000_00000000_00000000_00000000_00001010_00000010;
+        assert_eq!(expected, attackers_to(&chess_board, f3, Color::White, chess_board.occupancy()));
+        assert!(!chess_board.is_square_attacked(f3, Color::Black));
+    }
+
+    #[test]
+    fn test_between() {
+        let square = |name: &str| parse_square(name).unwrap();
+        assert_eq!(square("b1") | square("c1") | square("d1"), between(square("a1"), square("e1")));
+        assert_eq!(square("d4") | square("e5"), between(square("f6"), square("c3")));
+        assert_eq!(0, between(square("a1"), square("b1")));
+        assert_eq!(0, between(square("a1"), square("b3")));
+    }
+
+    #[test]
+    fn test_gives_check() {
+        let gives_check = |fen: &str, uci: &str| {
+            let chess_board = read_fen(fen);
+            chess_board.gives_check(&Move::from_uci(&chess_board, uci).unwrap())
+        };
+        assert!(gives_check("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1", "a1a8"));
+        assert!(!gives_check("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1", "a1a7"));
+        // Castling checks with the rook
+        assert!(!gives_check("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1", "e1c1"));
+        assert!(gives_check("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", "e1c1"));
+        // The knight uncovers the bishop, the pawn only checks after promoting
+        assert!(gives_check("7k/8/8/8/3N4/8/1B6/K7 w - - 0 1", "d4b5"));
+        assert!(gives_check("k7/4P3/8/8/8/8/8/K7 w - - 0 1", "e7e8q"));
+        assert!(!gives_check("k7/4P3/8/8/8/8/8/K7 w - - 0 1", "e7e8n"));
+        // En passant removes the pawn that blocked the rook
+        assert!(gives_check("8/8/8/k2pP2R/8/8/8/K7 w - d6 0 1", "e5d6"));
+    }
+
+    #[test]
+    fn test_is_in_check() {
+        let chess_board = read_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert!(chess_board.is_in_check());
+        let h4 = 0b00000000_00000000_00000000_00000000_00000001_00000000_00000000_00000000;
+        assert_eq!(h4, chess_board.checkers());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::movesets::sliders::SliderAttacks;
+use lazy_static::lazy_static;
+
+// Slider attacks with hyperbola quintessence: along a line, subtracting the square from the
+// occupancy sets every bit up to the first blocker above the square. Doing the same on the reversed
+// board gives the blockers below it. Only the four lines through every square are stored.
+
+lazy_static! {
+    // The rank, file, diagonal and anti-diagonal through every square, without the square itself
+    static ref LINE_MASKS: [[u64; 4]; 64] = generate_line_masks();
+}
+
+const RANK: usize = 0;
+const FILE: usize = 1;
+const DIAGONAL: usize = 2;
+const ANTI_DIAGONAL: usize = 3;
+
+/// Hyperbola quintessence with reversed bitboards, needs only the line masks.
+pub struct HyperbolaQuintessenceAttacks;
+
+impl SliderAttacks for HyperbolaQuintessenceAttacks {
+    #[inline]
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        let masks = &LINE_MASKS[index];
+        line_attacks(index, occupancy, masks[RANK]) | line_attacks(index, occupancy, masks[FILE])
+    }
+
+    #[inline]
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        let masks = &LINE_MASKS[index];
+        line_attacks(index, occupancy, masks[DIAGONAL]) | line_attacks(index, occupancy, masks[ANTI_DIAGONAL])
+    }
+}
+
+#[inline]
+fn line_attacks(index: usize, occupancy: u64, line_mask: u64) -> u64 {
+    let square = 1u64 << index;
+    let forward = occupancy & line_mask;
+    let reverse = forward.reverse_bits();
+    let forward = forward.wrapping_sub(square);
+    let reverse = reverse.wrapping_sub(square.reverse_bits());
+    (forward ^ reverse.reverse_bits()) & line_mask
+}
+
+fn generate_line_masks() -> [[u64; 4]; 64] {
+    let mut line_masks = [[0; 4]; 64];
+    for (index, masks) in line_masks.iter_mut().enumerate() {
+        let (row, col) = ((index / 8) as i32, (index % 8) as i32);
+        for other in (0..64).filter(|&other| other != index) {
+            let (other_row, other_col) = ((other / 8) as i32, (other % 8) as i32);
+            let bit = 1u64 << other;
+            if other_row == row {
+                masks[RANK] |= bit;
+            }
+            if other_col == col {
+                masks[FILE] |= bit;
+            }
+            if other_row - row == other_col - col {
+                masks[DIAGONAL] |= bit;
+            }
+            if other_row - row == col - other_col {
+                masks[ANTI_DIAGONAL] |= bit;
+            }
+        }
+    }
+    line_masks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_masks() {
+        // The corner at bit 0 has a full rank, file and one diagonal
+        assert_eq!(0xfe, LINE_MASKS[0][RANK]);
+        assert_eq!(0x0101_0101_0101_0100, LINE_MASKS[0][FILE]);
+        assert_eq!(0x8040_2010_0804_0200, LINE_MASKS[0][DIAGONAL]);
+        assert_eq!(0, LINE_MASKS[0][ANTI_DIAGONAL]);
+    }
+}
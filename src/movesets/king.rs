@@ -23,7 +23,7 @@ fn king_moves_south(king_position_ew : u64) -> u64{
     (king_position_ew & !RANK_ONE) >> 8
 }
 
-fn all_king_moves(king_position : u64) -> u64{
+pub(crate) fn all_king_moves(king_position : u64) -> u64{
     // Calculate king with east and west positions
     let king_position_ew = king_position | king_move_east(king_position) | king_move_west(king_position);
     king_position ^ (king_position_ew | king_moves_north(king_position_ew) | king_moves_south(king_position_ew))
@@ -37,12 +37,13 @@ fn get_king_attacks(all_king_moves: u64, opponent_pieces: u64) -> u64{
     all_king_moves & opponent_pieces
 }
 
-fn set_moves_attacks_kings(king: &mut PieceInfo, opponent_pieces: u64, empty_squares: u64){
+pub(crate) fn set_moves_attacks_kings(king: &mut PieceInfo, opponent_pieces: u64, empty_squares: u64){
     let all_king_moves = all_king_moves(king.positions);
     king.moves = get_king_soft_moves(all_king_moves, empty_squares);
     king.attacks = get_king_attacks(all_king_moves, opponent_pieces);
 }
 
+#[cfg(test)]
 mod tests{
     use super::*;
     use crate::chess::piece::{PieceInfo, Color};
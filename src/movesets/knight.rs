@@ -43,7 +43,7 @@ fn knight_move_sse(knight_position: u64) -> u64 {
     (knight_position & !H_FILE & !(RANK_ONE | RANK_TWO)) >> 17
 }
 
-fn all_knight_moves(knight_position: u64) -> u64 {
+pub(crate) fn all_knight_moves(knight_position: u64) -> u64 {
     knight_move_nee(knight_position) | knight_move_nne(knight_position) | knight_move_nnw(knight_position) | knight_move_nww(knight_position)
     | knight_move_see(knight_position) | knight_move_sse(knight_position) | knight_move_ssw(knight_position) | knight_move_sww(knight_position)
 }
@@ -56,12 +56,13 @@ fn get_knight_attacks(all_knight_moves: u64, opponent_pieces: u64) -> u64{
     all_knight_moves & opponent_pieces
 }
 
-fn set_moves_attacks_knights(knights: &mut PieceInfo, opponent_pieces: u64, empty_squares: u64){
+pub(crate) fn set_moves_attacks_knights(knights: &mut PieceInfo, opponent_pieces: u64, empty_squares: u64){
     let all_knight_moves = all_knight_moves(knights.positions);
     knights.moves = get_knight_soft_moves(all_knight_moves, empty_squares);
     knights.attacks = get_knight_attacks(all_knight_moves, opponent_pieces);
 }
 
+#[cfg(test)]
 mod tests{
     use super::*;
     use crate::chess::piece::{PieceInfo, Color};
@@ -145,7 +146,7 @@ mod tests{
 
     #[test]
     fn test_knight_move_nww_invalid_file(){
-        let knight_initial_position = 0b00000000_0000000_11000000_00000000_00000000_00000000_00000000_00000000;
+        let knight_initial_position = 0b00000000_00000000_11000000_00000000_00000000_00000000_00000000_00000000;
         let expected_result =       0;
         let result = knight_move_nww(knight_initial_position);
         assert_eq!(expected_result, result);
@@ -176,7 +177,7 @@ mod tests{
 
     #[test]
     fn test_knight_move_nnw_invalid_file(){
-        let knight_initial_position = 0b00000000_0000000_10000000_00000000_00000000_00000000_00000000_00000000;
+        let knight_initial_position = 0b00000000_00000000_10000000_00000000_00000000_00000000_00000000_00000000;
         let expected_result =       0;
         let result = knight_move_nnw(knight_initial_position);
         assert_eq!(expected_result, result);
@@ -207,7 +208,7 @@ mod tests{
 
     #[test]
     fn test_knight_move_sse_invalid_file(){
-        let knight_initial_position = 0b00000000_00000000_00000000_00000000_00000000_000000001_00000000_00000000;
+        let knight_initial_position = 0b00000000_00000000_00000000_00000000_00000000_00000001_00000000_00000000;
         let expected_result =       0;
         let result = knight_move_sse(knight_initial_position);
         assert_eq!(expected_result, result);
@@ -268,7 +269,7 @@ mod tests{
 
     #[test]
     fn test_knight_move_sww_invalid_file(){
-        let knight_initial_position = 0b00000000_0000000_00000000_00000000_00000000_11000000_00000000_00000000;
+        let knight_initial_position = 0b00000000_00000000_00000000_00000000_00000000_11000000_00000000_00000000;
         let expected_result =       0;
         let result = knight_move_sww(knight_initial_position);
         assert_eq!(expected_result, result);
@@ -299,7 +300,7 @@ mod tests{
 
     #[test]
     fn test_knight_move_ssw_invalid_file(){
-        let knight_initial_position = 0b00000000_0000000_00000000_00000000_00000000_10000000_00000000_00000000;
+        let knight_initial_position = 0b00000000_00000000_00000000_00000000_00000000_10000000_00000000_00000000;
         let expected_result =       0;
         let result = knight_move_ssw(knight_initial_position);
         assert_eq!(expected_result, result);
@@ -0,0 +1,65 @@
+use crate::movesets::sliders::SliderAttacks;
+use crate::utils::board_utils::{A_FILE, H_FILE};
+
+// Slider attacks from Kogge-Stone occluded fills, without any tables. The square slides in every
+// direction through the empty squares in three shifts of one, two and four steps.
+
+// Shift per step and the squares a step may land on, so it does not wrap around the board
+const NORTH: (i32, u64) = (8, !0);
+const SOUTH: (i32, u64) = (-8, !0);
+const EAST: (i32, u64) = (-1, !A_FILE);
+const WEST: (i32, u64) = (1, !H_FILE);
+const NORTH_EAST: (i32, u64) = (7, !A_FILE);
+const NORTH_WEST: (i32, u64) = (9, !H_FILE);
+const SOUTH_EAST: (i32, u64) = (-9, !A_FILE);
+const SOUTH_WEST: (i32, u64) = (-7, !H_FILE);
+
+/// Kogge-Stone fills, computed from scratch on every call.
+pub struct KoggeStoneAttacks;
+
+impl SliderAttacks for KoggeStoneAttacks {
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        let empty = !occupancy;
+        let square = 1 << index;
+        [NORTH, SOUTH, EAST, WEST].iter().fold(0, |attacks, &direction| attacks | sliding_attacks(square, empty, direction))
+    }
+
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        let empty = !occupancy;
+        let square = 1 << index;
+        [NORTH_EAST, NORTH_WEST, SOUTH_EAST, SOUTH_WEST].iter().fold(0, |attacks, &direction| attacks | sliding_attacks(square, empty, direction))
+    }
+}
+
+fn sliding_attacks(square: u64, empty: u64, (step, landing): (i32, u64)) -> u64 {
+    let mut generator = square;
+    let mut propagator = empty & landing;
+    generator |= propagator & shift(generator, step);
+    propagator &= shift(propagator, step);
+    generator |= propagator & shift(generator, 2 * step);
+    propagator &= shift(propagator, 2 * step);
+    generator |= propagator & shift(generator, 4 * step);
+    // One more step to include the first blocker
+    shift(generator, step) & landing
+}
+
+fn shift(bitboard: u64, amount: i32) -> u64 {
+    if amount > 0 { bitboard << amount } else { bitboard >> -amount }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::board_utils::parse_square;
+
+    #[test]
+    fn test_kogge_stone_attacks() {
+        let index = |name: &str| parse_square(name).unwrap().trailing_zeros() as usize;
+        let squares = |names: &[&str]| names.iter().fold(0, |bitboard, name| bitboard | parse_square(name).unwrap());
+        // Rook on d4 blocked on d6 and f4, the blockers themselves are attacked
+        let occupancy = squares(&["d4", "d6", "f4", "h4"]);
+        assert_eq!(squares(&["d5", "d6", "d3", "d2", "d1", "e4", "f4", "c4", "b4", "a4"]), KoggeStoneAttacks.rook_attacks(index("d4"), occupancy));
+        // Bishop on h1 does not wrap around to the a-file
+        assert_eq!(squares(&["g2", "f3", "e4"]), KoggeStoneAttacks.bishop_attacks(index("h1"), squares(&["h1", "e4"])));
+    }
+}
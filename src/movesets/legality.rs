@@ -0,0 +1,152 @@
+use crate::chess::chess_board::{get_empty_squares, ChessBoard};
+use crate::chess::chess_move::Move;
+use crate::chess::piece::{Color, PieceType};
+use crate::movesets::move_generator::{piece_targets, PROMOTION_PIECES};
+use crate::movesets::move_list::MoveList;
+use crate::movesets::pawn::*;
+use crate::utils::board_utils::{RANK_EIGHT, RANK_ONE};
+
+// Checks for single moves from outside the move generator, like hash moves, killers, user input or
+// opening books. They follow the same rules as the generator, but only look at the moved piece.
+
+impl ChessBoard {
+    /// Returns true if the move follows the movement rules of the piece in this position, as if
+    /// it came from `pseudo_legal_moves`. The own king may be left in check.
+    pub fn is_pseudo_legal(&self, chess_move: &Move) -> bool {
+        let color = self.active_color;
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        if from.count_ones() != 1 || to.count_ones() != 1 || self.piece_at(from) != Some((chess_move.piece_type, color)) {
+            return false;
+        }
+        if self.pieces_of(color) & to != 0 {
+            return false;
+        }
+
+        if chess_move.piece_type == PieceType::Pawn {
+            return self.is_pseudo_legal_pawn_move(chess_move);
+        }
+        if chess_move.promotion.is_some() {
+            return false;
+        }
+        if chess_move.is_castling() {
+            let mut castling_moves = MoveList::new();
+            self.add_castling_moves(&mut castling_moves, self.occupancy());
+            return castling_moves.contains(chess_move);
+        }
+        piece_targets(chess_move.piece_type, from, self.occupancy()) & to != 0
+    }
+
+    /// Returns true if the move is pseudo-legal and does not leave the own king in check.
+    pub fn is_legal(&self, chess_move: &Move) -> bool {
+        self.is_pseudo_legal(chess_move) && self.leaves_king_safe(chess_move)
+    }
+
+    fn is_pseudo_legal_pawn_move(&self, chess_move: &Move) -> bool {
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        // A pawn reaching the last rank has to promote, and only then
+        let promotes = to & (RANK_ONE | RANK_EIGHT) != 0;
+        match chess_move.promotion {
+            Some(promotion) if !promotes || !PROMOTION_PIECES.contains(&promotion) => return false,
+            None if promotes => return false,
+            _ => {}
+        }
+
+        let empty_squares = get_empty_squares(self);
+        let opponent_pieces = self.pieces_of(!self.active_color);
+        let targets = match self.active_color {
+            Color::White => {
+                white_pawn_single_push(from, empty_squares)
+                    | white_pawn_double_push(from, empty_squares)
+                    | white_pawn_attacks(from, opponent_pieces | self.en_passant)
+            }
+            Color::Black => {
+                black_pawn_single_push(from, empty_squares)
+                    | black_pawn_double_push(from, empty_squares)
+                    | black_pawn_attacks(from, opponent_pieces | self.en_passant)
+            }
+        };
+        targets & to != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::board_utils::{parse_square, squares};
+    use crate::movesets::test_positions::random_positions;
+    use crate::utils::fen_reader::read_fen;
+    use std::collections::HashSet;
+
+    fn uci(chess_board: &ChessBoard, uci: &str) -> Move {
+        Move::from_uci(chess_board, uci).unwrap()
+    }
+
+    // Every combination of a piece of the active color, a piece type, a target and a promotion,
+    // most of which are not even pseudo-legal
+    fn candidate_moves(chess_board: &ChessBoard) -> Vec<Move> {
+        let mut candidates = Vec::new();
+        for from in squares(chess_board.pieces_of(chess_board.active_color)) {
+            for to in squares(!0) {
+                for piece_type in PieceType::ALL {
+                    candidates.push(Move::new(piece_type, from, to));
+                }
+                for promotion in PieceType::ALL {
+                    candidates.push(Move::new_promotion(from, to, promotion));
+                }
+            }
+        }
+        candidates
+    }
+
+    #[test]
+    fn test_checks_agree_with_generator() {
+        for chess_board in random_positions(48, 7) {
+            let candidates = candidate_moves(&chess_board);
+            let pseudo_legal: HashSet<Move> = candidates.iter().filter(|&chess_move| chess_board.is_pseudo_legal(chess_move)).cloned().collect();
+            assert_eq!(chess_board.pseudo_legal_moves().into_iter().collect::<HashSet<_>>(), pseudo_legal, "{:?}", chess_board);
+            let legal: HashSet<Move> = pseudo_legal.into_iter().filter(|chess_move| chess_board.is_legal(chess_move)).collect();
+            assert_eq!(chess_board.legal_moves().into_iter().collect::<HashSet<_>>(), legal, "{:?}", chess_board);
+        }
+    }
+
+    #[test]
+    fn test_castling() {
+        let chess_board = read_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        assert!(chess_board.is_legal(&uci(&chess_board, "e1g1")));
+        assert!(chess_board.is_legal(&uci(&chess_board, "e1c1")));
+
+        // Through an attacked square, without the right and with a blocked path
+        let attacked = read_fen("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1");
+        assert!(!attacked.is_pseudo_legal(&Move::new(PieceType::King, parse_square("e1").unwrap(), parse_square("g1").unwrap())));
+        let no_right = read_fen("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1");
+        assert!(!no_right.is_pseudo_legal(&Move::new(PieceType::King, parse_square("e1").unwrap(), parse_square("g1").unwrap())));
+        let blocked = read_fen("r3k2r/8/8/8/8/8/8/RN2K2R w KQkq - 0 1");
+        assert!(!blocked.is_pseudo_legal(&Move::new(PieceType::King, parse_square("e1").unwrap(), parse_square("c1").unwrap())));
+    }
+
+    #[test]
+    fn test_en_passant_and_promotion() {
+        let chess_board = read_fen("4k3/P7/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        assert!(chess_board.is_legal(&uci(&chess_board, "e5d6")));
+        assert!(chess_board.is_legal(&uci(&chess_board, "a7a8n")));
+        // The promotion piece is required, and a pawn can not promote to a king or pawn
+        assert!(!chess_board.is_pseudo_legal(&Move::new(PieceType::Pawn, parse_square("a7").unwrap(), parse_square("a8").unwrap())));
+        assert!(!chess_board.is_pseudo_legal(&Move::new_promotion(parse_square("a7").unwrap(), parse_square("a8").unwrap(), PieceType::King)));
+        assert!(!chess_board.is_pseudo_legal(&Move::new_promotion(parse_square("e5").unwrap(), parse_square("e6").unwrap(), PieceType::Queen)));
+
+        // Without the en passant square the capture is gone
+        let no_en_passant = read_fen("4k3/P7/8/3pP3/8/8/8/4K3 w - - 0 1");
+        assert!(!no_en_passant.is_pseudo_legal(&Move::new(PieceType::Pawn, parse_square("e5").unwrap(), parse_square("d6").unwrap())));
+    }
+
+    #[test]
+    fn test_pinned_piece_is_pseudo_legal_but_not_legal() {
+        let chess_board = read_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1");
+        let knight_move = Move::new(PieceType::Knight, parse_square("e2").unwrap(), parse_square("c3").unwrap());
+        assert!(chess_board.is_pseudo_legal(&knight_move));
+        assert!(!chess_board.is_legal(&knight_move));
+        // A move of the wrong color or a piece that is not there
+        assert!(!chess_board.is_pseudo_legal(&Move::new(PieceType::Rook, parse_square("e7").unwrap(), parse_square("e6").unwrap())));
+        assert!(!chess_board.is_pseudo_legal(&Move::new(PieceType::Queen, parse_square("e2").unwrap(), parse_square("e3").unwrap())));
+    }
+}
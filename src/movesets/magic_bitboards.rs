@@ -1,24 +1,99 @@
-use crate::{utils::board_utils::{RANKS, FILES}, chess::piece::{PieceInfo, PieceType}};
+use crate::{utils::board_utils::{RANKS, FILES}, chess::piece::PieceType};
+use crate::movesets::magic_numbers::{BISHOP_INDEX_BITS, BISHOP_MAGICS, ROOK_INDEX_BITS, ROOK_MAGICS};
 use lazy_static::lazy_static;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 
-// This class generate plain magic numbers and look-up tables for the Rooks and Bishops. 
+// This class generate magic numbers and look-up tables for the Rooks and Bishops.
+// The magic numbers are searched once with the generate_magics tool and checked in as magic_numbers.rs,
+// at runtime the look-up tables are filled from them without any search. The tables used by default
+// are "fancy" magics: one contiguous table for all squares, each square starting at its own offset.
+// They are filled straight from the blockermasks, the blockerboards and moveboards of all squares
+// are only built for the magic search, the tests and the other slider backends.
 
 lazy_static! {
     pub(crate) static ref BLOCKERMASKS_ROOK: [u64; 64] = generate_all_blockermasks(PieceType::Rook);
     pub(crate) static ref BLOCKERBOARDS_ROOK: Vec<Vec<u64>> = generate_all_blockerboards(&BLOCKERMASKS_ROOK);
-    static ref MOVEBOARDS_ROOK: Vec<Vec<u64>>= generate_all_moveboards(&BLOCKERBOARDS_ROOK, PieceType::Rook);
-    pub(crate) static ref MAGIC_TUPLE_ROOK : ([u64; 64], Vec<Vec<Option<u64>>>)  = generate_magic_numbers(&BLOCKERBOARDS_ROOK ,&MOVEBOARDS_ROOK, &BLOCKERMASKS_ROOK);
+    pub(crate) static ref MOVEBOARDS_ROOK: Vec<Vec<u64>>= generate_all_moveboards(&BLOCKERBOARDS_ROOK, PieceType::Rook);
+    pub(crate) static ref FANCY_MAGICS_ROOK: FancyMagics = FancyMagics::new(&ROOK_MAGICS, &ROOK_INDEX_BITS, &BLOCKERMASKS_ROOK, PieceType::Rook);
 
     pub(crate) static ref BLOCKERMASKS_BISHOP: [u64; 64] = generate_all_blockermasks(PieceType::Bishop);
     pub(crate) static ref BLOCKERBOARDS_BISHOP: Vec<Vec<u64>> = generate_all_blockerboards(&BLOCKERMASKS_BISHOP);
-    static ref MOVEBOARDS_BISHOP: Vec<Vec<u64>> = generate_all_moveboards(&BLOCKERBOARDS_BISHOP, PieceType::Bishop);
-    pub(crate) static ref MAGIC_TUPLE_BISHOP : ([u64; 64], Vec<Vec<Option<u64>>>)  = generate_magic_numbers(&BLOCKERBOARDS_BISHOP ,&MOVEBOARDS_BISHOP, &BLOCKERMASKS_BISHOP);
+    pub(crate) static ref MOVEBOARDS_BISHOP: Vec<Vec<u64>> = generate_all_moveboards(&BLOCKERBOARDS_BISHOP, PieceType::Bishop);
+    pub(crate) static ref FANCY_MAGICS_BISHOP: FancyMagics = FancyMagics::new(&BISHOP_MAGICS, &BISHOP_INDEX_BITS, &BLOCKERMASKS_BISHOP, PieceType::Bishop);
 
 }
 
-pub(crate) fn initialize(){
-    lazy_static::initialize(&MAGIC_TUPLE_ROOK);
-    lazy_static::initialize(&MAGIC_TUPLE_BISHOP);
+pub fn initialize(){
+    lazy_static::initialize(&FANCY_MAGICS_ROOK);
+    lazy_static::initialize(&FANCY_MAGICS_BISHOP);
+}
+
+/// Look-up tables for one piece type where the moveboards of all squares share a single table
+/// without gaps. Every square only keeps its blockermask, magic number, shift and the offset of its
+/// part of the table, so a look-up is a multiplication, a shift and one load.
+pub struct FancyMagics {
+    squares: [FancyMagicSquare; 64],
+    moveboards: Vec<u64>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct FancyMagicSquare {
+    blockermask: u64,
+    magic_number: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl FancyMagics {
+    /// Builds the shared table from magic numbers that are known to work, panics otherwise.
+    pub fn new(magic_numbers: &[u64; 64], index_bits: &[u8; 64], blockermask: &[u64; 64], piece_name: PieceType) -> FancyMagics {
+        let mut squares = [FancyMagicSquare::default(); 64];
+        let mut shared_table = vec![0u64; index_bits.iter().map(|&bits| 1 << bits).sum()];
+        let mut offset = 0;
+        for i in 0..64 {
+            let square = FancyMagicSquare {
+                blockermask: blockermask[i],
+                magic_number: magic_numbers[i],
+                shift: 64 - index_bits[i] as u32,
+                offset,
+            };
+            // Walk all subsets of the blockermask and write each moveboard at its magic index. A
+            // slider always has a move, so a 0 entry is still free. No blockerboard maps to the
+            // entries that stay empty, so they are never looked up.
+            let table = &mut shared_table[offset..offset + (1 << index_bits[i])];
+            let mut blockerboard: u64 = 0;
+            loop {
+                let moveboard = generate_moveboard(piece_name, i as i8, blockerboard);
+                let entry = &mut table[((blockerboard.wrapping_mul(square.magic_number)) >> square.shift) as usize];
+                if *entry != 0 && *entry != moveboard {
+                    panic!("Invalid magic number {} for square {}", magic_numbers[i], i);
+                }
+                *entry = moveboard;
+                blockerboard = blockerboard.wrapping_sub(blockermask[i]) & blockermask[i];
+                if blockerboard == 0 {
+                    break;
+                }
+            }
+            squares[i] = square;
+            offset += 1 << index_bits[i];
+        }
+        FancyMagics { squares, moveboards: shared_table }
+    }
+
+    /// The moveboard of the piece on the square with the given index for this occupancy.
+    #[inline]
+    pub fn moveboard(&self, index: usize, occupancy: u64) -> u64 {
+        let square = &self.squares[index];
+        let magic_index = ((occupancy & square.blockermask).wrapping_mul(square.magic_number)) >> square.shift;
+        self.moveboards[square.offset + magic_index as usize]
+    }
+
+    /// The number of entries in the shared table.
+    pub fn table_size(&self) -> usize {
+        self.moveboards.len()
+    }
 }
 
 pub(crate) fn blockermask_rook (square: u64) -> u64 {
@@ -122,12 +197,12 @@ fn generate_bishop_move_pattern(row: i8, col: i8) -> u64 {
 
 pub fn generate_all_blockermasks(piece_name: PieceType) -> [u64; 64]{
     let mut blockermasks: [u64; 64] = [0; 64];
-    for i in 0..64{
+    for (i, blockermask) in blockermasks.iter_mut().enumerate(){
         let square = 1 << i;
         if piece_name == PieceType::Rook {
-            blockermasks[i] = blockermask_rook(square);
+            *blockermask = blockermask_rook(square);
         } else {
-            blockermasks[i] = blockermask_bishop(square);
+            *blockermask = blockermask_bishop(square);
         }
     }
     blockermasks
@@ -151,7 +226,7 @@ fn generate_blockerboard(index: u32, blockermask: u64) -> u64 {
     blockerboard
 }
 
-fn generate_blockerboards_for_square(square: u8, blockermask: &[u64; 64], blockerboards: &mut Vec<Vec<u64>>) {
+fn generate_blockerboards_for_square(square: u8, blockermask: &[u64; 64], blockerboards: &mut [Vec<u64>]) {
     let bits = blockermask[square as usize].count_ones();
     
     // Generate all possible combinations of bits in the blockermask.
@@ -218,6 +293,29 @@ fn generate_moveboard_for_square(square: u64, move_pattern : u64, blockerboard :
 
 }
 
+// Walks the four diagonals from the square and stops each ray at the first blocker, which is included
+fn generate_bishop_moveboard_for_square(square: u64, blockerboard: u64) -> u64 {
+    let index = square.trailing_zeros() as i8;
+    let row: i8 = index / 8;
+    let col: i8 = index % 8;
+    let mut moveboard = 0;
+
+    for (row_step, col_step) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let mut i = row + row_step;
+        let mut j = col + col_step;
+        while (0..8).contains(&i) && (0..8).contains(&j) {
+            let next_bit = 1 << (8 * i + j);
+            moveboard |= next_bit;
+            if next_bit & blockerboard != 0 {
+                break;
+            }
+            i += row_step;
+            j += col_step;
+        }
+    }
+    moveboard
+}
+
 fn clear_axes(clear_switch: &mut bool, moveboard: &mut u64, row: i8, col: i8, axes_to_clear: i8, blockerboard: u64, axis: [u64;8]) {
     // if the switch is active, clear the row
     if *clear_switch{
@@ -231,27 +329,31 @@ fn clear_axes(clear_switch: &mut bool, moveboard: &mut u64, row: i8, col: i8, ax
     }
 }
 
-pub fn generate_all_moveboards(blockerboards: &Vec<Vec<u64>>, piece_name: PieceType) -> Vec<Vec<u64>>{
+pub fn generate_all_moveboards(blockerboards: &[Vec<u64>], piece_name: PieceType) -> Vec<Vec<u64>>{
     // Q1 Does the move pattern match the blockerboard by index?
     //let move_pattern = generate_all_move_patterns(piece_name);
     let array_size = blockerboards[0].len();
     let mut moveboards = vec![vec![0u64; array_size]; 64];
     for i in 0..64{
-        let square = 1 << i;
-        let move_pattern ;
-        if piece_name == PieceType::Rook {
-            move_pattern = generate_rook_move_pattern(i / 8 as i8, i % 8 as i8);
-        } else {
-            move_pattern = generate_bishop_move_pattern(i / 8 as i8, i % 8 as i8);
-        }
-        
         for j in 0..array_size{
-            moveboards[i as usize][j] = generate_moveboard_for_square(square, move_pattern, blockerboards[i as usize][j]);
+            moveboards[i as usize][j] = generate_moveboard(piece_name, i, blockerboards[i as usize][j]);
         }
     }
     moveboards
 }
 
+// The moveboard of a rook or bishop on the square with the given index for one blockerboard
+fn generate_moveboard(piece_name: PieceType, index: i8, blockerboard: u64) -> u64 {
+    let square = 1 << index;
+    if piece_name == PieceType::Rook {
+        let move_pattern = generate_rook_move_pattern(index / 8, index % 8);
+        generate_moveboard_for_square(square, move_pattern, blockerboard)
+    } else {
+        generate_bishop_moveboard_for_square(square, blockerboard)
+    }
+}
+
+#[cfg(test)]
 fn generate_all_move_patterns(piece_name: PieceType) -> [u64; 64] {
     let mut move_pattern: [u64; 64] = [0; 64];
     for y in 0..8{
@@ -267,43 +369,107 @@ fn generate_all_move_patterns(piece_name: PieceType) -> [u64; 64] {
 }
 
 
-pub fn generate_magic_numbers(blockerboards: &Vec<Vec<u64>>, moveboards: &Vec<Vec<u64>>, blockermask: &[u64; 64]) -> ([u64; 64], Vec<Vec<Option<u64>>>) {
-
-    let mut magic_numbers: [u64; 64] = [0; 64];
-    //magic_numbers.par_iter_mut().enumerate().for_each(|(i, magic_number)| {
-        //create an empty vector
-    let mut magic_tables: Vec<Vec<Option<u64>>> = vec![vec![None; 4096]; 64];
+/// Fills the look-up tables for magic numbers that are known to work, e.g. the checked-in ones.
+/// Each square uses `index_bits` bits of the product as index into its table.
+/// Panics if a magic number maps two blockerboards with different moveboards to the same index.
+pub fn generate_magic_tables(magic_numbers: &[u64; 64], index_bits: &[u8; 64], blockerboards: &[Vec<u64>], moveboards: &[Vec<u64>], blockermask: &[u64; 64]) -> Vec<Vec<Option<u64>>> {
+    (0..64)
+        .map(|i| {
+            let entries = 1 << blockermask[i].count_ones();
+            try_magic_number(magic_numbers[i], index_bits[i] as u32, &blockerboards[i][..entries], &moveboards[i][..entries])
+                .unwrap_or_else(|| panic!("Invalid magic number {} for square {}", magic_numbers[i], i))
+        })
+        .collect()
+}
 
-    for i in 0..64{
-        let bits = blockermask[i].count_ones();
-        let mut magic= 0;
-        let mut found_magic_number = false;
-        while !found_magic_number {
-            let mut table = vec![None; 1 << bits];
-            magic = rand::random::<u64>() & rand::random::<u64>() & rand::random::<u64>();
-            let mut magic_number_found = true;
-            for j in 0..(1 << bits){
-                let blockerboard = blockerboards[i][j as usize];
-                let index = (blockerboard.wrapping_mul(magic)) >> (64 - bits);
-                if table[index as usize] == None {
-                    table[index as usize] = Some(moveboards[i][j as usize]);
-                } else {
-                    magic_number_found = false;
-                    break;
+/// Searches a magic number for every square, in parallel. Every square draws its candidates from its
+/// own random number generator seeded with `seed` and the square, so a seed always gives the same
+/// magic numbers. This is slow, it is only used by the generate_magics tool to write magic_numbers.rs.
+pub fn generate_magic_numbers(blockerboards: &[Vec<u64>], moveboards: &[Vec<u64>], blockermask: &[u64; 64], seed: u64) -> ([u64; 64], Vec<Vec<Option<u64>>>) {
+    let results: Vec<(u64, Vec<Option<u64>>)> = (0..64)
+        .into_par_iter()
+        .map(|i| {
+            let bits = blockermask[i].count_ones();
+            let entries = 1 << bits;
+            let mut rng = square_rng(seed, i);
+            loop {
+                let magic = random_magic_candidate(&mut rng, 3);
+                if let Some(table) = try_magic_number(magic, bits, &blockerboards[i][..entries], &moveboards[i][..entries]) {
+                    return (magic, table);
                 }
             }
-            if magic_number_found {
-                println!("Found magic number for square {} : {}", i, magic);
-                found_magic_number = true;
-                magic_tables[i] = table;
-            }
-        }
+        })
+        .collect();
+
+    let mut magic_numbers = [0; 64];
+    let mut magic_tables = Vec::with_capacity(64);
+    for (i, (magic, table)) in results.into_iter().enumerate() {
         magic_numbers[i] = magic;
+        magic_tables.push(table);
     }
     (magic_numbers, magic_tables)
 }
 
+/// Searches "better" magic numbers that need fewer index bits than the blockermask has bits, which
+/// gives smaller tables. Starting from a plain magic number, every square tries `attempts` candidates
+/// for one bit less and keeps going as long as it finds one. Returns the magic numbers and their index bits.
+pub fn generate_better_magic_numbers(blockerboards: &[Vec<u64>], moveboards: &[Vec<u64>], blockermask: &[u64; 64], seed: u64, attempts: u64) -> ([u64; 64], [u8; 64]) {
+    let (plain_magic_numbers, _) = generate_magic_numbers(blockerboards, moveboards, blockermask, seed);
+    let results: Vec<(u64, u8)> = (0..64)
+        .into_par_iter()
+        .map(|i| {
+            let entries = 1 << blockermask[i].count_ones();
+            let mut best = (plain_magic_numbers[i], blockermask[i].count_ones() as u8);
+            let mut rng = square_rng(seed.wrapping_add(1), i);
+            'bits: while best.1 > 1 {
+                for attempt in 0..attempts {
+                    // Magic numbers with fewer bits are rarer, also try denser candidates for them
+                    let magic = random_magic_candidate(&mut rng, attempt as u32 % 3 + 1);
+                    if try_magic_number(magic, best.1 as u32 - 1, &blockerboards[i][..entries], &moveboards[i][..entries]).is_some() {
+                        best = (magic, best.1 - 1);
+                        continue 'bits;
+                    }
+                }
+                break;
+            }
+            best
+        })
+        .collect();
+
+    let mut magic_numbers = [0; 64];
+    let mut index_bits = [0; 64];
+    for (i, (magic, bits)) in results.into_iter().enumerate() {
+        magic_numbers[i] = magic;
+        index_bits[i] = bits;
+    }
+    (magic_numbers, index_bits)
+}
+
+// Builds the table for one square, or returns None if two blockerboards with different moveboards
+// get the same index. Blockerboards with the same moveboard may share an entry.
+fn try_magic_number(magic: u64, index_bits: u32, blockerboards: &[u64], moveboards: &[u64]) -> Option<Vec<Option<u64>>> {
+    let mut table = vec![None; 1 << index_bits];
+    for (blockerboard, &moveboard) in blockerboards.iter().zip(moveboards) {
+        let index = (blockerboard.wrapping_mul(magic)) >> (64 - index_bits);
+        match table[index as usize] {
+            Some(existing) if existing != moveboard => return None,
+            _ => table[index as usize] = Some(moveboard),
+        }
+    }
+    Some(table)
+}
+
+fn square_rng(seed: u64, square: usize) -> StdRng {
+    StdRng::seed_from_u64(seed.wrapping_mul(64).wrapping_add(square as u64))
+}
+
+// Magic numbers with few bits set work best, so combine a few random numbers
+fn random_magic_candidate(rng: &mut StdRng, numbers: u32) -> u64 {
+    (0..numbers).fold(u64::MAX, |magic, _| magic & rng.gen::<u64>())
+}
 
+
+#[cfg(test)]
 mod tests{
 
     use super::*;
@@ -348,7 +514,7 @@ mod tests{
     #[test]
     fn test_remove_edges(){
         let row: u8 = 1;
-        let mut blocker_mask: u64 = 0b11111111_10000001_10000001_10000001_10000001_10000001_10000001_11111111;
+        let blocker_mask: u64 = 0b11111111_10000001_10000001_10000001_10000001_10000001_10000001_11111111;
         let  col:u8 = 1;
         let expected_result:u64 =   0;
         let actual = remove_edges(row, blocker_mask, col);
@@ -358,7 +524,7 @@ mod tests{
     #[test]
     fn test_remove_edges_corner(){
         let row: u8 = 0;
-        let mut blocker_mask: u64 = 0b11111111_10000001_10000001_10000001_10000001_10000001_10000001_11111111;
+        let blocker_mask: u64 = 0b11111111_10000001_10000001_10000001_10000001_10000001_10000001_11111111;
         let  col:u8 = 0;
         let expected_result:u64 =   0b00000000_00000001_00000001_00000001_00000001_00000001_00000001_01111111;
         let actual = remove_edges(row, blocker_mask, col);
@@ -403,6 +569,15 @@ mod tests{
         
     }
 
+    #[test]
+    fn test_generate_bishop_moveboard_for_square(){
+        let square: u64 =           0b00000000_00000000_00000000_00010000_00000000_00000000_00000000_00000000;
+        let blockerboard : u64 =    0b00000000_00000000_00100000_00000000_00000000_00000010_00000000_00000000;
+        let expected_result : u64 = 0b00000010_00000100_00101000_00000000_00101000_01000100_10000010_00000001;
+        let actual_result = generate_bishop_moveboard_for_square(square, blockerboard);
+        assert_eq!(expected_result, actual_result);
+    }
+
     #[test]
     fn test_generate_all_move_patterns_rook_not_empty(){
         let result = generate_all_move_patterns(PieceType::Rook);
@@ -436,6 +611,90 @@ mod tests{
     //     assert!(blockermasks.iter().all(|&x| x != 0));
     // }
 
+    #[test]
+    fn test_checked_in_magic_numbers(){
+        let checked_in = [(PieceType::Rook, ROOK_MAGICS, ROOK_INDEX_BITS), (PieceType::Bishop, BISHOP_MAGICS, BISHOP_INDEX_BITS)];
+        for (piece_type, magic_numbers, index_bits) in checked_in {
+            let blockermasks = generate_all_blockermasks(piece_type);
+            let blockerboards = generate_all_blockerboards(&blockermasks);
+            let moveboards = generate_all_moveboards(&blockerboards, piece_type);
+            // Panics on a wrong magic number
+            let magic_tables = generate_magic_tables(&magic_numbers, &index_bits, &blockerboards, &moveboards, &blockermasks);
+            for i in 0..64 {
+                let bits = blockermasks[i].count_ones();
+                for j in 0..(1 << bits) {
+                    let index = (blockerboards[i][j].wrapping_mul(magic_numbers[i])) >> (64 - index_bits[i]);
+                    assert_eq!(Some(moveboards[i][j]), magic_tables[i][index as usize]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fancy_magics(){
+        // One entry per index, without any gaps between the squares
+        let rook_size: usize = ROOK_INDEX_BITS.iter().map(|&bits| 1 << bits).sum();
+        let bishop_size: usize = BISHOP_INDEX_BITS.iter().map(|&bits| 1 << bits).sum();
+        assert_eq!(rook_size, FANCY_MAGICS_ROOK.table_size());
+        assert_eq!(bishop_size, FANCY_MAGICS_BISHOP.table_size());
+
+        let pieces = [(&*FANCY_MAGICS_ROOK, &*BLOCKERBOARDS_ROOK, &*MOVEBOARDS_ROOK), (&*FANCY_MAGICS_BISHOP, &*BLOCKERBOARDS_BISHOP, &*MOVEBOARDS_BISHOP)];
+        for (fancy_magics, blockerboards, moveboards) in pieces {
+            for i in 0..64 {
+                for (blockerboard, moveboard) in blockerboards[i].iter().zip(&moveboards[i]) {
+                    // Pieces outside the blockermask must not change the moveboard
+                    assert_eq!(*moveboard, fancy_magics.moveboard(i, *blockerboard));
+                    assert_eq!(*moveboard, fancy_magics.moveboard(i, *blockerboard | 1 << i));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid magic number")]
+    fn test_fancy_magics_invalid_magic_number(){
+        let mut magic_numbers = ROOK_MAGICS;
+        magic_numbers[0] = 1;
+        FancyMagics::new(&magic_numbers, &ROOK_INDEX_BITS, &BLOCKERMASKS_ROOK, PieceType::Rook);
+    }
+
+    #[test]
+    fn test_generate_magic_numbers_is_deterministic(){
+        let blockermasks = generate_all_blockermasks(PieceType::Bishop);
+        let blockerboards = generate_all_blockerboards(&blockermasks);
+        let moveboards = generate_all_moveboards(&blockerboards, PieceType::Bishop);
+        let (first, _) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks, 7);
+        let (second, _) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks, 7);
+        let (other, _) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks, 8);
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_generate_better_magic_numbers(){
+        let blockermasks = generate_all_blockermasks(PieceType::Bishop);
+        let blockerboards = generate_all_blockerboards(&blockermasks);
+        let moveboards = generate_all_moveboards(&blockerboards, PieceType::Bishop);
+        let (magic_numbers, index_bits) = generate_better_magic_numbers(&blockerboards, &moveboards, &blockermasks, 7, 1000);
+        // Never more bits than the plain magic numbers, and the tables must still be correct
+        assert!((0..64).all(|i| index_bits[i] as u32 <= blockermasks[i].count_ones()));
+        generate_magic_tables(&magic_numbers, &index_bits, &blockerboards, &moveboards, &blockermasks);
+    }
+
+    #[test]
+    fn test_better_magic_number_with_fewer_bits(){
+        // Found for the bishop on g1 with one bit less than its blockermask has, which only works
+        // because blockerboards with the same moveboard share an entry
+        let blockermasks = generate_all_blockermasks(PieceType::Bishop);
+        let blockerboards = generate_all_blockerboards(&blockermasks);
+        let moveboards = generate_all_moveboards(&blockerboards, PieceType::Bishop);
+        let bits = blockermasks[1].count_ones();
+        let entries = 1 << bits;
+        let table = try_magic_number(0x4e61637d3e47fe97, bits - 1, &blockerboards[1][..entries], &moveboards[1][..entries]).unwrap();
+        assert_eq!(1 << (bits - 1), table.len());
+        assert!(try_magic_number(0x4e61637d3e47fe97, bits - 2, &blockerboards[1][..entries], &moveboards[1][..entries]).is_none());
+    }
+
     #[test]
     #[ignore]
     fn test_generate_magic_number_rook(){
@@ -443,7 +702,7 @@ mod tests{
         let blockermasks = generate_all_blockermasks(piece_name);
         let blockerboards = generate_all_blockerboards(&blockermasks);
         let moveboards = generate_all_moveboards(&blockerboards, PieceType::Rook);
-        let (magic_numbers, magic_tables) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks);
+        let (magic_numbers, magic_tables) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks, 1);
         // test if each blockerboard * magic_number maps gives an index of an element in the magic table that is equal to the moveboard
         for i in 0..64{
             let blockermask = blockermasks[i];
@@ -467,7 +726,7 @@ mod tests{
         let blockermasks = generate_all_blockermasks(piece_name);
         let blockerboards = generate_all_blockerboards(&blockermasks);
         let moveboards = generate_all_moveboards(&blockerboards, PieceType::Bishop);
-        let (magic_numbers, magic_tables) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks);
+        let (magic_numbers, magic_tables) = generate_magic_numbers(&blockerboards, &moveboards, &blockermasks, 1);
         // test if each blockerboard * magic_number maps gives an index of an element in the magic table that is equal to the moveboard
         for i in 0..64{
             let blockermask = blockermasks[i];
@@ -484,4 +743,4 @@ mod tests{
 
     }
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,63 @@
+// Magic numbers for the rook and bishop look-up tables and the number of index bits they use,
+// indexed by square.
+// Generated by `cargo run --release --bin generate_magics -- --seed 1`, do not edit by hand.
+
+pub(crate) const ROOK_MAGICS: [u64; 64] = [
+    0x4680002330804004, 0x0100106040008500, 0x0080200188100081, 0x208010000e802800,
+    0x22000a0120045008, 0x6100040081002802, 0x0080020000801100, 0x118008688000c100,
+    0x0040800a60814004, 0x3000808040002000, 0x0091007041002000, 0x2001000900201001,
+    0xa001000500100800, 0x11020008a200300c, 0x0401006600210004, 0x8402000043042082,
+    0x2004208002824002, 0x1196808040002000, 0x0030008020008131, 0x4900220042000810,
+    0x0000808018000401, 0x4400808002000401, 0x802a010100040200, 0x0000220004440081,
+    0x1804410100218000, 0x8400a00040045000, 0x062001c040300800, 0x1c01002100081000,
+    0x080c008080080086, 0x10060002002490c8, 0x80d0080400020110, 0x0500800080004100,
+    0x1408800443002302, 0x21a0003000c00140, 0x0000200088801000, 0x9212100080800800,
+    0x000a002052001488, 0x0114810c00800200, 0x400200a302000804, 0x0401028042000504,
+    0x6084822040108000, 0x0460008040008030, 0x8001001020050040, 0x0128005000818029,
+    0x0111000408010010, 0x400100080401000e, 0x0b05000600090004, 0x1060010448820004,
+    0x4080118040002080, 0x005000e001400140, 0x9880100020028080, 0x0400100008048180,
+    0x024900101c080100, 0x8028040042008080, 0x2021100288010400, 0x8100144400930200,
+    0x8800401025008009, 0x0050110200244086, 0x0400b02004400901, 0x0002600408100101,
+    0x0022000820055002, 0x0192001001044802, 0x0840500106408804, 0x004100089020c201,
+];
+
+pub(crate) const ROOK_INDEX_BITS: [u8; 64] = [
+    12, 11, 11, 11, 11, 11, 11, 12,
+    11, 10, 10, 10, 10, 10, 10, 11,
+    11, 10, 10, 10, 10, 10, 10, 11,
+    11, 10, 10, 10, 10, 10, 10, 11,
+    11, 10, 10, 10, 10, 10, 10, 11,
+    11, 10, 10, 10, 10, 10, 10, 11,
+    11, 10, 10, 10, 10, 10, 10, 11,
+    12, 11, 11, 11, 11, 11, 11, 12,
+];
+
+pub(crate) const BISHOP_MAGICS: [u64; 64] = [
+    0x009010020800c010, 0x00241084aa008020, 0x0042020200201010, 0x000410c200080110,
+    0x0a220a1014144402, 0x0002011018000810, 0x0904012410443000, 0x060201814c108404,
+    0x0000411084470040, 0x00a8480208484100, 0x40861020a2004000, 0x0412240404800044,
+    0x000c420210080000, 0x0040111002100088, 0x1040740228820800, 0x010006010c420242,
+    0x004c040888188800, 0x1220200204040881, 0x8001024820410200, 0x0608020420202010,
+    0x1910840400a04000, 0x000a40a808080401, 0x4000440406121102, 0x5402c2908400c800,
+    0x0309200008200120, 0x1010060190040102, 0x4022480005080100, 0x0001080004004090,
+    0x0000840160802000, 0x0008410002012108, 0x1042020402411002, 0x0009818016b40400,
+    0x0810108840901210, 0x0002080400021040, 0x008a002204100081, 0x02004018204a0200,
+    0x00100104300a0200, 0x0141230900120040, 0x4001180201210101, 0x0011040480090061,
+    0x0041301011330c09, 0x020404040c000200, 0x0802020102414410, 0x2400020612004400,
+    0x000244010a000402, 0x00200414a2000020, 0xa312100311010610, 0x0042082051000282,
+    0x000180c820109860, 0x8001c10410429210, 0x2230004044500600, 0x00822c804202100a,
+    0x0840004010248000, 0x408211a041010400, 0x80c00c0142220280, 0x0804100099310018,
+    0x2808140202100400, 0x0000064118211080, 0x00300081c2080421, 0x9800101018420200,
+    0x0241000140050504, 0x8014292088100120, 0x0080082008126144, 0x60400401ca020020,
+];
+
+pub(crate) const BISHOP_INDEX_BITS: [u8; 64] = [
+    6, 5, 5, 5, 5, 5, 5, 6,
+    5, 5, 5, 5, 5, 5, 5, 5,
+    5, 5, 7, 7, 7, 7, 5, 5,
+    5, 5, 7, 9, 9, 7, 5, 5,
+    5, 5, 7, 9, 9, 7, 5, 5,
+    5, 5, 7, 7, 7, 7, 5, 5,
+    5, 5, 5, 5, 5, 5, 5, 5,
+    6, 5, 5, 5, 5, 5, 5, 6,
+];
@@ -0,0 +1,395 @@
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::{get_empty_squares, ChessBoard, WHITE_KING_START, BLACK_KING_START};
+use crate::chess::chess_move::Move;
+use crate::chess::piece::{Color, PieceType};
+use crate::movesets::attacks::{attackers_to, between};
+use crate::movesets::bishop::bishop_move;
+use crate::movesets::king::{all_king_moves, set_moves_attacks_kings};
+use crate::movesets::move_list::MoveList;
+use crate::movesets::knight::{all_knight_moves, set_moves_attacks_knights};
+use crate::movesets::pawn::*;
+use crate::movesets::queen::queen_move;
+use crate::movesets::rook::rook_move;
+use crate::utils::board_utils::{squares, RANK_EIGHT, RANK_ONE};
+
+pub(crate) const PROMOTION_PIECES: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+
+/// Which pseudo-legal moves to generate.
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+pub enum GenerationMode {
+    // Every move
+    All,
+    // Captures, including en passant, and all promotions
+    Captures,
+    // Moves that neither capture nor promote, including castling
+    Quiets,
+    // Quiet moves that give check, directly or by uncovering a slider
+    QuietChecks,
+    // Moves of the king, captures of the checking piece and blocks of its line to the king, for
+    // when the active color is in check. Without a check these are all moves.
+    Evasions,
+}
+
+impl ChessBoard {
+    /// Generates all moves for the active color that follow the movement rules of the pieces,
+    /// without checking whether the own king is left in check.
+    pub fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut moves = MoveList::new();
+        self.generate_moves(GenerationMode::All, &mut moves);
+        moves.to_vec()
+    }
+
+    /// Generates all legal moves for the active color.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = MoveList::new();
+        self.generate_legal_moves(GenerationMode::All, &mut moves);
+        moves.to_vec()
+    }
+
+    /// Adds the pseudo-legal moves of the given mode to `moves`, without allocating.
+    pub fn generate_moves(&self, mode: GenerationMode, moves: &mut MoveList) {
+        self.generate_moves_from(!0, mode, moves);
+    }
+
+    /// Adds the legal moves of the given mode to `moves`.
+    pub fn generate_legal_moves(&self, mode: GenerationMode, moves: &mut MoveList) {
+        let start = moves.len();
+        self.generate_moves(mode, moves);
+        moves.retain_from(start, |chess_move| self.leaves_king_safe(chess_move));
+    }
+
+    /// Adds the pseudo-legal moves of the given mode for the pieces on the `sources` squares.
+    pub(crate) fn generate_moves_from(&self, sources: u64, mode: GenerationMode, moves: &mut MoveList) {
+        match mode {
+            GenerationMode::All => self.add_moves(moves, sources, !0, true, true),
+            GenerationMode::Captures => self.add_moves(moves, sources, !0, true, false),
+            GenerationMode::Quiets => self.add_moves(moves, sources, !0, false, true),
+            GenerationMode::QuietChecks => {
+                let start = moves.len();
+                self.add_moves(moves, sources, !0, false, true);
+                moves.retain_from(start, |chess_move| self.gives_check(chess_move));
+            }
+            GenerationMode::Evasions => self.add_evasions(moves, sources),
+        }
+    }
+
+    // Adds the moves of the pieces on `sources` that end on `targets`, with the captures (which
+    // include promotions), the quiet moves or both. Castling is never a move out of check, so it
+    // does not need to be restricted to the targets.
+    fn add_moves(&self, moves: &mut MoveList, sources: u64, targets: u64, captures: bool, quiets: bool) {
+        let color = self.active_color;
+        let opponent_pieces = self.pieces_of(!color);
+        let empty_squares = get_empty_squares(self);
+        let occupancy = !empty_squares;
+        let mut allowed_targets = 0;
+        if captures {
+            allowed_targets |= opponent_pieces;
+        }
+        if quiets {
+            allowed_targets |= empty_squares;
+        }
+
+        self.add_pawn_moves(moves, sources, targets, captures, quiets);
+        for piece_type in [PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen, PieceType::King] {
+            for square in squares(self.get_piece_info(piece_type, color).positions & sources) {
+                for target in squares(piece_targets(piece_type, square, occupancy) & allowed_targets & targets) {
+                    moves.push(Move::new(piece_type, square, target));
+                }
+            }
+        }
+        if quiets && self.king_square(color) & sources != 0 {
+            self.add_castling_moves(moves, occupancy);
+        }
+    }
+
+    fn add_evasions(&self, moves: &mut MoveList, sources: u64) {
+        let checkers = self.checkers();
+        if checkers == 0 {
+            self.add_moves(moves, sources, !0, true, true);
+            return;
+        }
+        let king = self.king_square(self.active_color);
+        self.add_moves(moves, sources & king, !0, true, true);
+        // Against a double check only the king can move
+        if checkers.count_ones() == 1 {
+            self.add_moves(moves, sources & !king, checkers | between(king, checkers), true, true);
+        }
+    }
+
+    /// Returns true if playing the pseudo-legal move does not leave the own king attacked.
+    pub(crate) fn leaves_king_safe(&self, chess_move: &Move) -> bool {
+        let color = self.active_color;
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        let king = self.king_square(color);
+        if king == 0 {
+            return true;
+        }
+        // The occupancy after the move, without the pawn that en passant takes from behind the
+        // target. A captured piece is masked out of the attackers, as it is still on its bitboard.
+        let captured = match chess_move.piece_type {
+            PieceType::Pawn if to == self.en_passant => match color {
+                Color::White => to >> 8,
+                Color::Black => to << 8,
+            },
+            _ => to,
+        };
+        let occupancy = (self.occupancy() & !from & !captured) | to;
+        // A moving king is tested on its target square, which for castling was the only square
+        // not checked yet. The rook it castles with never shields the king's target square.
+        let king = if chess_move.piece_type == PieceType::King { to } else { king };
+        attackers_to(self, king, !color, occupancy) & !captured == 0
+    }
+
+    /// Returns true if the active color is checkmated.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check() && self.legal_moves().is_empty()
+    }
+
+    /// Returns true if the active color has no legal moves but is not in check.
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check() && self.legal_moves().is_empty()
+    }
+
+    /// Fills the `moves` and `attacks` bitboards of every piece type for both colors, as seen from
+    /// the current position. Moves are squares a piece can go to, attacks are opponent pieces it can take.
+    pub fn update_moves_and_attacks(&mut self) {
+        let empty_squares = get_empty_squares(self);
+        let occupancy = !empty_squares;
+        for color in [Color::White, Color::Black] {
+            let opponent_pieces = self.pieces_of(!color);
+            let en_passant = if color == self.active_color { self.en_passant } else { 0 };
+            let last_move = self.move_history.last().cloned();
+
+            let pawns = self.get_mutable_piece_info(PieceType::Pawn, color);
+            pawns.moves = get_pawn_moves(pawns.positions, empty_squares, color);
+            pawns.attacks = match (&last_move, color) {
+                (Some(last_move), _) if en_passant != 0 => get_pawn_attack_set(last_move, pawns.positions, opponent_pieces, color),
+                (_, Color::White) => white_pawn_attacks(pawns.positions, opponent_pieces | en_passant),
+                (_, Color::Black) => black_pawn_attacks(pawns.positions, opponent_pieces | en_passant),
+            };
+
+            set_moves_attacks_knights(self.get_mutable_piece_info(PieceType::Knight, color), opponent_pieces, empty_squares);
+            set_moves_attacks_kings(self.get_mutable_piece_info(PieceType::King, color), opponent_pieces, empty_squares);
+
+            for piece_type in [PieceType::Bishop, PieceType::Rook, PieceType::Queen] {
+                let pieces = self.get_mutable_piece_info(piece_type, color);
+                let targets = squares(pieces.positions)
+                    .fold(0, |targets, square| targets | piece_targets(piece_type, square, occupancy));
+                pieces.moves = targets & empty_squares;
+                pieces.attacks = targets & opponent_pieces;
+            }
+        }
+    }
+
+    fn add_pawn_moves(&self, moves: &mut MoveList, sources: u64, targets: u64, captures: bool, quiets: bool) {
+        let color = self.active_color;
+        let empty_squares = get_empty_squares(self);
+        let opponent_pieces = self.pieces_of(!color);
+        // En passant captures the pawn behind the target square, which may be the one giving check
+        let en_passant_victim = match color {
+            Color::White => self.en_passant >> 8,
+            Color::Black => self.en_passant << 8,
+        };
+        let targets = if en_passant_victim & targets != 0 { targets | self.en_passant } else { targets };
+
+        for pawn in squares(self.get_piece_info(PieceType::Pawn, color).positions & sources) {
+            let (pushes, attacks) = match color {
+                Color::White => (
+                    white_pawn_single_push(pawn, empty_squares) | white_pawn_double_push(pawn, empty_squares),
+                    white_pawn_attacks(pawn, opponent_pieces | self.en_passant),
+                ),
+                Color::Black => (
+                    black_pawn_single_push(pawn, empty_squares) | black_pawn_double_push(pawn, empty_squares),
+                    black_pawn_attacks(pawn, opponent_pieces | self.en_passant),
+                ),
+            };
+            for target in squares((pushes | attacks) & targets) {
+                let promotes = target & (RANK_ONE | RANK_EIGHT) != 0;
+                let is_capture = promotes || target & attacks != 0;
+                if (is_capture && !captures) || (!is_capture && !quiets) {
+                    continue;
+                }
+                if promotes {
+                    for promotion in PROMOTION_PIECES {
+                        moves.push(Move::new_promotion(pawn, target, promotion));
+                    }
+                } else {
+                    moves.push(Move::new(PieceType::Pawn, pawn, target));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn add_castling_moves(&self, moves: &mut MoveList, occupancy: u64) {
+        let color = self.active_color;
+        let king = match color {
+            Color::White => WHITE_KING_START,
+            Color::Black => BLACK_KING_START,
+        };
+        if self.king_square(color) != king {
+            return;
+        }
+        for castling in [Castling::KingSide, Castling::QueenSide] {
+            if !self.has_castling_right(color, castling) {
+                continue;
+            }
+            // Squares between king and rook must be empty, the king may not pass through an attack
+            let (between, passed, target, rook) = match castling {
+                Castling::KingSide => (king >> 1 | king >> 2, king >> 1, king >> 2, king >> 3),
+                Castling::QueenSide => (king << 1 | king << 2 | king << 3, king << 1, king << 2, king << 4),
+            };
+            if occupancy & between != 0 || self.get_piece_info(PieceType::Rook, color).positions & rook == 0 {
+                continue;
+            }
+            if self.is_square_attacked(king, !color) || self.is_square_attacked(passed, !color) {
+                continue;
+            }
+            moves.push(Move::new(PieceType::King, king, target));
+        }
+    }
+}
+
+/// Returns the squares a non-pawn piece on `square` can reach, including own pieces.
+pub(crate) fn piece_targets(piece_type: PieceType, square: u64, occupancy: u64) -> u64 {
+    match piece_type {
+        PieceType::Knight => all_knight_moves(square),
+        PieceType::Bishop => bishop_move(square, occupancy),
+        PieceType::Rook => rook_move(square, occupancy),
+        PieceType::Queen => queen_move(square, occupancy),
+        PieceType::King => all_king_moves(square),
+        PieceType::Pawn => panic!("Pawn targets depend on the color and are generated separately"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::chess_move::parse_uci;
+    use crate::movesets::test_positions::{random_positions, KIWIPETE};
+    use crate::utils::fen_reader::read_fen;
+    use std::collections::HashSet;
+
+    fn legal_move_set(chess_board: &ChessBoard, mode: GenerationMode) -> HashSet<Move> {
+        let mut moves = MoveList::new();
+        chess_board.generate_legal_moves(mode, &mut moves);
+        let set: HashSet<Move> = moves.iter().cloned().collect();
+        assert_eq!(moves.len(), set.len(), "Duplicate moves in {:?}", mode);
+        set
+    }
+
+    #[test]
+    fn test_legal_moves_start_position() {
+        let chess_board = ChessBoard::new(false);
+        assert_eq!(20, chess_board.legal_moves().len());
+    }
+
+    #[test]
+    fn test_legal_moves_kiwipete() {
+        let chess_board = read_fen(KIWIPETE);
+        assert_eq!(48, chess_board.legal_moves().len());
+    }
+
+    #[test]
+    fn test_legal_moves_en_passant_and_promotion() {
+        let chess_board = read_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1");
+        assert_eq!(24, chess_board.legal_moves().len());
+
+        let chess_board = read_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1");
+        assert_eq!(14, chess_board.legal_moves().len());
+    }
+
+    #[test]
+    fn test_leaves_king_safe() {
+        let is_safe = |fen: &str, uci: &str| {
+            let chess_board = read_fen(fen);
+            let (from, to, _) = parse_uci(uci).unwrap();
+            let (piece_type, _) = chess_board.piece_at(from).unwrap();
+            chess_board.leaves_king_safe(&Move::new(piece_type, from, to))
+        };
+        // The king can not step back along the line of the rook checking it
+        assert!(!is_safe("4k3/8/8/8/8/4K3/8/4r3 w - - 0 1", "e3e2"));
+        assert!(is_safe("4k3/8/8/8/8/4K3/8/4r3 w - - 0 1", "e3d2"));
+        // Taking the checker, unless it is protected
+        assert!(is_safe("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1", "e1e2"));
+        assert!(!is_safe("4k3/8/8/8/8/4r3/4r3/4K3 w - - 0 1", "e1e2"));
+        // En passant removes both pawns from the rank between the king and the rook
+        assert!(!is_safe("8/8/8/KPp4r/8/8/8/7k w - c6 0 1", "b5c6"));
+        assert!(is_safe("8/8/8/KPp5/8/8/8/7k w - c6 0 1", "b5c6"));
+        // Moving a pinned piece along the pin
+        assert!(is_safe("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1", "e2e7"));
+        assert!(!is_safe("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1", "e2d2"));
+    }
+
+    #[test]
+    fn test_generation_modes_agree_with_legal_moves() {
+        for chess_board in random_positions(44, 30) {
+            let legal_moves = chess_board.legal_moves();
+            let is_noisy = |chess_move: &Move| {
+                chess_move.promotion.is_some()
+                    || chess_board.piece_at(chess_move.new_position).is_some()
+                    || (chess_move.piece_type == PieceType::Pawn && chess_move.new_position == chess_board.en_passant)
+            };
+            let gives_check = |chess_move: &Move| {
+                let mut next = chess_board.clone();
+                next.make_move(chess_move);
+                next.is_in_check()
+            };
+            let filtered = |keep: &dyn Fn(&Move) -> bool| legal_moves.iter().filter(|chess_move| keep(chess_move)).cloned().collect::<HashSet<Move>>();
+
+            let fen = crate::utils::fen_writer::write_fen(&chess_board);
+            assert_eq!(filtered(&|_| true), legal_move_set(&chess_board, GenerationMode::All), "{}", fen);
+            assert_eq!(filtered(&is_noisy), legal_move_set(&chess_board, GenerationMode::Captures), "{}", fen);
+            assert_eq!(filtered(&|chess_move| !is_noisy(chess_move)), legal_move_set(&chess_board, GenerationMode::Quiets), "{}", fen);
+            assert_eq!(filtered(&|chess_move| !is_noisy(chess_move) && gives_check(chess_move)), legal_move_set(&chess_board, GenerationMode::QuietChecks), "{}", fen);
+            if chess_board.is_in_check() {
+                assert_eq!(filtered(&|_| true), legal_move_set(&chess_board, GenerationMode::Evasions), "{}", fen);
+            }
+            for chess_move in &legal_moves {
+                assert_eq!(gives_check(chess_move), chess_board.gives_check(chess_move), "{} {}", fen, chess_move.to_uci());
+            }
+        }
+    }
+
+    #[test]
+    fn test_evasions_are_only_king_moves_captures_and_blocks() {
+        // Double check by the knight and rook, only the king may move
+        let double_check = read_fen("4k3/8/5N2/8/1b6/8/8/4R2K b - - 0 1");
+        let mut moves = MoveList::new();
+        double_check.generate_moves(GenerationMode::Evasions, &mut moves);
+        assert!(moves.iter().all(|chess_move| chess_move.piece_type == PieceType::King));
+
+        // The knight can block on c3 or d2, castling out of check is not allowed
+        let chess_board = read_fen("4k3/8/8/8/1q6/8/8/1N2K2R w K - 0 1");
+        let mut moves = MoveList::new();
+        chess_board.generate_moves(GenerationMode::Evasions, &mut moves);
+        let blocks: Vec<String> = moves.iter().filter(|chess_move| chess_move.piece_type != PieceType::King).map(Move::to_uci).collect();
+        assert_eq!(vec!["b1d2", "b1c3"], blocks);
+        assert!(!moves.iter().any(|chess_move| chess_move.to_uci() == "e1g1"));
+
+        // The pawn takes the checking pawn en passant
+        let en_passant = read_fen("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1");
+        let mut moves = MoveList::new();
+        en_passant.generate_legal_moves(GenerationMode::Evasions, &mut moves);
+        assert!(moves.iter().any(|chess_move| chess_move.to_uci() == "e4d3"));
+        assert!(moves.iter().all(|chess_move| chess_move.piece_type == PieceType::King || chess_move.to_uci() == "e4d3"));
+    }
+
+    #[test]
+    fn test_update_moves_and_attacks() {
+        let mut chess_board = read_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2");
+        chess_board.update_moves_and_attacks();
+        let d5 = 0b00000000_00000000_00000000_00010000_00000000_00000000_00000000_00000000;
+        assert_eq!(d5, chess_board.white_pawns_immutable().attacks);
+        assert_eq!(0b00000000_00000000_00000000_00000000_00000000_10100101_00001000_00000000, chess_board.white_knights_immutable().moves);
+        assert_eq!(0, chess_board.white_rooks_immutable().moves);
+        assert_eq!(d5 << 8 | d5 << 16, chess_board.black_queens_immutable().moves);
+    }
+
+    #[test]
+    fn test_checkmate_and_stalemate() {
+        let fools_mate = read_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert!(fools_mate.is_checkmate());
+        let stalemate = read_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert!(stalemate.is_stalemate());
+    }
+}
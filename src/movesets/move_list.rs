@@ -0,0 +1,137 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::chess::chess_move::Move;
+use crate::chess::piece::PieceType;
+
+/// More than the number of legal or pseudo-legal moves in any reachable position.
+pub const MAX_MOVES: usize = 256;
+
+// Fills the unused part of the list
+const NO_MOVE: Move = Move { piece_type: PieceType::Pawn, old_position: 0, new_position: 0, promotion: None };
+
+/// A list of moves with a fixed capacity of `MAX_MOVES` that lives on the stack, so generating moves
+/// during search does not allocate. Dereferences to a slice of the moves in it.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> MoveList {
+        MoveList { moves: [NO_MOVE; MAX_MOVES], len: 0 }
+    }
+
+    /// Adds a move at the end, panics if the list is full.
+    #[inline]
+    pub fn push(&mut self, chess_move: Move) {
+        assert!(self.len < MAX_MOVES, "MoveList can hold at most {} moves", MAX_MOVES);
+        self.moves[self.len] = chess_move;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Removes the move at `index` and puts the last move in its place.
+    pub fn swap_remove(&mut self, index: usize) -> Move {
+        assert!(index < self.len, "Index {} out of range for a MoveList of {} moves", index, self.len);
+        self.len -= 1;
+        self.moves.swap(index, self.len);
+        std::mem::replace(&mut self.moves[self.len], NO_MOVE)
+    }
+
+    /// Keeps only the moves for which `keep` returns true, in their original order.
+    pub fn retain(&mut self, keep: impl FnMut(&Move) -> bool) {
+        self.retain_from(0, keep);
+    }
+
+    /// Like `retain`, but the moves before `start` are all kept.
+    pub fn retain_from(&mut self, start: usize, mut keep: impl FnMut(&Move) -> bool) {
+        let mut kept = start;
+        for index in start..self.len {
+            if keep(&self.moves[index]) {
+                self.moves.swap(kept, index);
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        MoveList::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::board_utils::parse_square;
+
+    fn knight_move(from: &str, to: &str) -> Move {
+        Move::new(PieceType::Knight, parse_square(from).unwrap(), parse_square(to).unwrap())
+    }
+
+    #[test]
+    fn test_move_list() {
+        let mut moves = MoveList::new();
+        assert!(moves.is_empty());
+        moves.push(knight_move("g1", "f3"));
+        moves.push(knight_move("g1", "h3"));
+        moves.push(knight_move("b1", "c3"));
+        assert_eq!(3, moves.len());
+        assert_eq!(knight_move("g1", "h3"), moves[1]);
+        assert!(moves.contains(&knight_move("b1", "c3")));
+
+        moves.retain(|chess_move| chess_move.new_position != parse_square("h3").unwrap());
+        assert_eq!(vec![knight_move("g1", "f3"), knight_move("b1", "c3")], moves.to_vec());
+
+        assert_eq!(knight_move("g1", "f3"), moves.swap_remove(0));
+        assert_eq!(vec![knight_move("b1", "c3")], moves.iter().cloned().collect::<Vec<_>>());
+
+        moves.clear();
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "MoveList can hold at most 256 moves")]
+    fn test_move_list_full() {
+        let mut moves = MoveList::new();
+        for _ in 0..=MAX_MOVES {
+            moves.push(knight_move("g1", "f3"));
+        }
+    }
+}
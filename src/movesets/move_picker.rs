@@ -0,0 +1,232 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::Move;
+use crate::chess::piece::PieceType;
+use crate::movesets::move_generator::GenerationMode;
+use crate::movesets::move_list::{MoveList, MAX_MOVES};
+
+// Staged move ordering for search. Most nodes are cut off by the first few moves, so the moves are
+// generated in stages and each stage only when the previous ones did not cause a cut-off.
+
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+enum Stage {
+    HashMove,
+    GenerateCaptures,
+    Captures,
+    Killers,
+    GenerateQuiets,
+    Quiets,
+    Done,
+}
+
+/// Yields the legal moves of a position in the order a search wants to try them: the hash move,
+/// captures and promotions ordered by MVV-LVA, the killer moves and then the other quiet moves.
+/// Moves are checked for legality only when they are yielded.
+pub struct MovePicker<'a> {
+    chess_board: &'a ChessBoard,
+    hash_move: Option<Move>,
+    killers: [Option<Move>; 2],
+    stage: Stage,
+    moves: MoveList,
+    scores: [i32; MAX_MOVES],
+    index: usize,
+}
+
+impl<'a> MovePicker<'a> {
+    /// The hash move and killers may come from other positions, they are only yielded if they are
+    /// legal here. Killers are only used if they are quiet moves.
+    pub fn new(chess_board: &'a ChessBoard, hash_move: Option<Move>, killers: [Option<Move>; 2]) -> MovePicker<'a> {
+        MovePicker {
+            chess_board,
+            hash_move,
+            killers,
+            stage: Stage::HashMove,
+            moves: MoveList::new(),
+            scores: [0; MAX_MOVES],
+            index: 0,
+        }
+    }
+
+    // The hash move and killers are generated again from their source square to make sure they are
+    // valid in this position, which is much cheaper than generating all moves
+    fn is_valid(&self, chess_move: &Move, mode: GenerationMode) -> bool {
+        let mut moves = MoveList::new();
+        self.chess_board.generate_moves_from(chess_move.old_position, mode, &mut moves);
+        moves.contains(chess_move) && self.chess_board.leaves_king_safe(chess_move)
+    }
+
+    fn is_hash_move_or_killer(&self, chess_move: &Move) -> bool {
+        self.hash_move.as_ref() == Some(chess_move) || self.killers.iter().any(|killer| killer.as_ref() == Some(chess_move))
+    }
+}
+
+impl Iterator for MovePicker<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::HashMove => {
+                    self.stage = Stage::GenerateCaptures;
+                    if let Some(hash_move) = self.hash_move.clone() {
+                        if self.is_valid(&hash_move, GenerationMode::All) {
+                            return Some(hash_move);
+                        }
+                    }
+                }
+                Stage::GenerateCaptures => {
+                    self.chess_board.generate_moves(GenerationMode::Captures, &mut self.moves);
+                    for (index, chess_move) in self.moves.iter().enumerate() {
+                        self.scores[index] = mvv_lva(self.chess_board, chess_move);
+                    }
+                    self.index = 0;
+                    self.stage = Stage::Captures;
+                }
+                Stage::Captures => {
+                    if self.index == self.moves.len() {
+                        self.index = 0;
+                        self.stage = Stage::Killers;
+                        continue;
+                    }
+                    // Selection sort, the remaining captures are usually not needed
+                    let best = (self.index..self.moves.len()).max_by_key(|&index| (self.scores[index], -(index as i32))).unwrap();
+                    self.moves.swap(self.index, best);
+                    self.scores.swap(self.index, best);
+                    let chess_move = self.moves[self.index].clone();
+                    self.index += 1;
+                    if self.hash_move.as_ref() != Some(&chess_move) && self.chess_board.leaves_king_safe(&chess_move) {
+                        return Some(chess_move);
+                    }
+                }
+                Stage::Killers => {
+                    if self.index == self.killers.len() {
+                        self.stage = Stage::GenerateQuiets;
+                        continue;
+                    }
+                    let killer = self.killers[self.index].clone();
+                    self.index += 1;
+                    if let Some(killer) = killer {
+                        let repeated = self.hash_move.as_ref() == Some(&killer) || (self.index == 2 && self.killers[0].as_ref() == Some(&killer));
+                        if !repeated && self.is_valid(&killer, GenerationMode::Quiets) {
+                            return Some(killer);
+                        }
+                    }
+                }
+                Stage::GenerateQuiets => {
+                    self.moves.clear();
+                    self.chess_board.generate_moves(GenerationMode::Quiets, &mut self.moves);
+                    self.index = 0;
+                    self.stage = Stage::Quiets;
+                }
+                Stage::Quiets => {
+                    if self.index == self.moves.len() {
+                        self.stage = Stage::Done;
+                        continue;
+                    }
+                    let chess_move = self.moves[self.index].clone();
+                    self.index += 1;
+                    if !self.is_hash_move_or_killer(&chess_move) && self.chess_board.leaves_king_safe(&chess_move) {
+                        return Some(chess_move);
+                    }
+                }
+                Stage::Done => return None,
+            }
+        }
+    }
+}
+
+/// Most valuable victim, least valuable attacker: captures of big pieces by small pieces first.
+/// Promotions count as capturing the piece that is promoted to.
+pub fn mvv_lva(chess_board: &ChessBoard, chess_move: &Move) -> i32 {
+    let victim = match chess_board.piece_at(chess_move.new_position) {
+        Some((piece_type, _)) => piece_value(piece_type),
+        // En passant, the only capture to an empty square
+        None if chess_move.piece_type == PieceType::Pawn && chess_move.new_position == chess_board.en_passant => piece_value(PieceType::Pawn),
+        None => 0,
+    };
+    let promotion = chess_move.promotion.map_or(0, piece_value);
+    (victim + promotion) * 8 - piece_value(chess_move.piece_type)
+}
+
+// Only the order matters
+fn piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 1,
+        PieceType::Knight => 2,
+        PieceType::Bishop => 3,
+        PieceType::Rook => 4,
+        PieceType::Queen => 5,
+        PieceType::King => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movesets::test_positions::{random_positions, KIWIPETE};
+    use crate::utils::board_utils::parse_square;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::fen_writer::write_fen;
+    use std::collections::HashSet;
+
+    fn uci(chess_board: &ChessBoard, uci: &str) -> Move {
+        Move::from_uci(chess_board, uci).unwrap()
+    }
+
+    #[test]
+    fn test_picker_yields_all_legal_moves_once() {
+        for chess_board in random_positions(43, 4) {
+            let fen = write_fen(&chess_board);
+            let legal_moves = chess_board.legal_moves();
+            let hash_move = legal_moves.last().cloned();
+            let killers = [legal_moves.first().cloned(), legal_moves.get(1).cloned()];
+            let picked: Vec<Move> = MovePicker::new(&chess_board, hash_move, killers).collect();
+            assert_eq!(legal_moves.len(), picked.len(), "{}", fen);
+            assert_eq!(legal_moves.into_iter().collect::<HashSet<_>>(), picked.into_iter().collect::<HashSet<_>>(), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_picker_order() {
+        let chess_board = read_fen(KIWIPETE);
+        let hash_move = uci(&chess_board, "e2a6");
+        let killer = uci(&chess_board, "a2a3");
+        let picked: Vec<Move> = MovePicker::new(&chess_board, Some(hash_move.clone()), [Some(killer.clone()), None]).collect();
+
+        assert_eq!(hash_move, picked[0]);
+        // Then the other captures with the best MVV-LVA score first
+        let captures = chess_board.legal_moves().iter().filter(|chess_move| chess_board.piece_at(chess_move.new_position).is_some()).count() - 1;
+        let scores: Vec<i32> = picked[1..=captures].iter().map(|chess_move| mvv_lva(&chess_board, chess_move)).collect();
+        assert!(picked[1..=captures].iter().all(|chess_move| chess_board.piece_at(chess_move.new_position).is_some()));
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert_eq!(killer, picked[captures + 1]);
+        assert_eq!(1, picked.iter().filter(|&chess_move| *chess_move == hash_move).count());
+    }
+
+    #[test]
+    fn test_picker_skips_invalid_hash_move_and_killers() {
+        let chess_board = ChessBoard::new(false);
+        let e2 = parse_square("e2").unwrap();
+        // A pawn move of three squares and a knight move to an occupied square
+        let hash_move = Move::new(PieceType::Pawn, e2, e2 << 24);
+        let killer = Move::new(PieceType::Knight, parse_square("g1").unwrap(), parse_square("e2").unwrap());
+        let picked: Vec<Move> = MovePicker::new(&chess_board, Some(hash_move), [Some(killer), None]).collect();
+        assert_eq!(20, picked.len());
+    }
+
+    #[test]
+    fn test_mvv_lva() {
+        let chess_board = read_fen("4k3/8/2n5/1P1q4/8/8/8/4K2Q w - - 0 1");
+        // Queen takes queen is better than pawn takes knight, quiet moves come last
+        let queen_takes_queen = mvv_lva(&chess_board, &uci(&chess_board, "h1d5"));
+        let pawn_takes_knight = mvv_lva(&chess_board, &uci(&chess_board, "b5c6"));
+        assert!(queen_takes_queen > pawn_takes_knight);
+        assert!(pawn_takes_knight > 0);
+        assert!(mvv_lva(&chess_board, &uci(&chess_board, "e1f1")) < 0);
+
+        // En passant takes a pawn and promotions count as taking the new piece
+        let en_passant = read_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        assert_eq!(7, mvv_lva(&en_passant, &uci(&en_passant, "e5d6")));
+        let promotion = read_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(39, mvv_lva(&promotion, &uci(&promotion, "a7a8q")));
+    }
+}
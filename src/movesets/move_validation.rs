@@ -0,0 +1,378 @@
+use std::fmt;
+
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::{ChessBoard, BLACK_KING_START, WHITE_KING_START};
+use crate::chess::chess_move::{parse_uci, Move};
+use crate::chess::piece::{Color, PieceType};
+use crate::movesets::attacks::between;
+use crate::movesets::move_generator::{piece_targets, PROMOTION_PIECES};
+use crate::movesets::pawn::{black_pawn_attacks, white_pawn_attacks};
+use crate::utils::board_utils::{square_name, square_to_xy, squares, RANK_EIGHT, RANK_ONE, RANK_SEVEN, RANK_TWO};
+use crate::utils::san::{parse_san_parts, SanParts};
+
+// Explains why a move typed by a user is rejected. The checks follow the rules one at a time, so
+// the reason is the first rule the move breaks.
+
+/// Why a move can not be played in a position.
+#[derive(PartialEq, Debug, Clone)]
+pub enum IllegalMove {
+    // The text is not a move in the notation that was used
+    InvalidNotation(String),
+    NoPiece { square: u64 },
+    WrongColor { square: u64, color: Color },
+    // No piece of the active color and the given type fits the SAN
+    NoPieceOfType(PieceType),
+    OwnPieceOnTarget { square: u64 },
+    CannotMoveThatWay { piece_type: PieceType, square: u64 },
+    // The first occupied square between the source and the target
+    PathBlocked { square: u64 },
+    LeavesKingInCheck,
+    // Castling rules about attacked squares: the king may not castle while in check, pass an
+    // attacked square or land on one
+    CastlingOutOfCheck,
+    CastlingThroughCheck { square: u64 },
+    CastlingIntoCheck { square: u64 },
+    CastlingRightsLost(Castling),
+    PromotionMissing,
+    InvalidPromotion(PieceType),
+    // The legal moves that fit the SAN
+    AmbiguousSan(Vec<Move>),
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalMove::InvalidNotation(text) => write!(f, "'{}' is not a valid move", text),
+            IllegalMove::NoPiece { square } => write!(f, "There is no piece on {}", square_name(*square)),
+            IllegalMove::WrongColor { square, color } => {
+                write!(f, "The piece on {} is {:?}, but it is {:?} to move", square_name(*square), color, !*color)
+            }
+            IllegalMove::NoPieceOfType(piece_type) => write!(f, "There is no {} that can make this move", piece_name(*piece_type)),
+            IllegalMove::OwnPieceOnTarget { square } => write!(f, "{} is occupied by an own piece", square_name(*square)),
+            IllegalMove::CannotMoveThatWay { piece_type, square } => {
+                write!(f, "The {} on {} can't move that way", piece_name(*piece_type), square_name(*square))
+            }
+            IllegalMove::PathBlocked { square } => write!(f, "The path is blocked on {}", square_name(*square)),
+            IllegalMove::LeavesKingInCheck => write!(f, "The move leaves the king in check"),
+            IllegalMove::CastlingOutOfCheck => write!(f, "The king can't castle out of check"),
+            IllegalMove::CastlingThroughCheck { square } => write!(f, "The king can't castle through the attacked square {}", square_name(*square)),
+            IllegalMove::CastlingIntoCheck { square } => write!(f, "The king can't castle into check on {}", square_name(*square)),
+            IllegalMove::CastlingRightsLost(Castling::KingSide) => write!(f, "The right to castle king side is lost"),
+            IllegalMove::CastlingRightsLost(Castling::QueenSide) => write!(f, "The right to castle queen side is lost"),
+            IllegalMove::PromotionMissing => write!(f, "The pawn must promote, add the piece to promote to"),
+            IllegalMove::InvalidPromotion(piece_type) => write!(f, "The move can't promote to a {}", piece_name(*piece_type)),
+            IllegalMove::AmbiguousSan(candidates) => {
+                let moves: Vec<String> = candidates.iter().map(|chess_move| chess_move.to_uci()).collect();
+                write!(f, "The move is ambiguous, it could be {}", moves.join(", "))
+            }
+        }
+    }
+}
+
+impl ChessBoard {
+    /// Returns Ok if the move is legal, otherwise the first rule it breaks. Agrees with `is_legal`.
+    pub fn validate_move(&self, chess_move: &Move) -> Result<(), IllegalMove> {
+        let color = self.active_color;
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        let Some((piece_type, piece_color)) = self.piece_at(from).filter(|_| from.count_ones() == 1) else {
+            return Err(IllegalMove::NoPiece { square: from });
+        };
+        if piece_color != color {
+            return Err(IllegalMove::WrongColor { square: from, color: piece_color });
+        }
+        if piece_type != chess_move.piece_type || to.count_ones() != 1 {
+            return Err(IllegalMove::CannotMoveThatWay { piece_type, square: from });
+        }
+        if self.pieces_of(color) & to != 0 {
+            return Err(IllegalMove::OwnPieceOnTarget { square: to });
+        }
+
+        match piece_type {
+            PieceType::Pawn => self.validate_pawn_move(chess_move)?,
+            _ if chess_move.promotion.is_some() => return Err(IllegalMove::InvalidPromotion(piece_type)),
+            PieceType::King if chess_move.is_castling() => self.validate_castling(chess_move)?,
+            _ => {
+                if piece_targets(piece_type, from, 0) & to == 0 {
+                    return Err(IllegalMove::CannotMoveThatWay { piece_type, square: from });
+                }
+                let blockers = between(from, to) & self.occupancy();
+                if blockers != 0 {
+                    return Err(IllegalMove::PathBlocked { square: nearest_square(blockers, from) });
+                }
+            }
+        }
+
+        // Anything the rules above missed is still caught by the generator's rules
+        if !self.is_pseudo_legal(chess_move) {
+            return Err(IllegalMove::CannotMoveThatWay { piece_type, square: from });
+        }
+        if !self.leaves_king_safe(chess_move) {
+            return Err(IllegalMove::LeavesKingInCheck);
+        }
+        Ok(())
+    }
+
+    /// Reads a move in UCI notation, e.g. `e2e4` or `e7e8q`, and returns it if it is legal.
+    pub fn validate_uci(&self, uci: &str) -> Result<Move, IllegalMove> {
+        let (from, to, promotion) = parse_uci(uci).ok_or(IllegalMove::InvalidNotation(uci.to_string()))?;
+        let Some((piece_type, _)) = self.piece_at(from) else {
+            return Err(IllegalMove::NoPiece { square: from });
+        };
+        let chess_move = Move { piece_type, old_position: from, new_position: to, promotion };
+        self.validate_move(&chess_move)?;
+        Ok(chess_move)
+    }
+
+    /// Reads a move in SAN and returns it if exactly one legal move fits. If none fits, the reason
+    /// is taken from the move of a piece that fits the SAN and comes closest to being legal.
+    pub fn validate_san(&self, san: &str) -> Result<Move, IllegalMove> {
+        let parts = parse_san_parts(san).ok_or(IllegalMove::InvalidNotation(san.to_string()))?;
+        let mut candidates: Vec<Move> = self.legal_moves().into_iter().filter(|chess_move| parts.matches(chess_move)).collect();
+        match candidates.len() {
+            0 => {}
+            1 => return Ok(candidates.remove(0)),
+            _ => return Err(IllegalMove::AmbiguousSan(candidates)),
+        }
+
+        let (piece_type, moves) = self.san_moves(&parts);
+        let reason = moves.iter().filter_map(|chess_move| self.validate_move(chess_move).err()).min_by_key(closeness);
+        Err(reason.unwrap_or(IllegalMove::NoPieceOfType(piece_type)))
+    }
+
+    // The moves of the pieces that fit the SAN, legal or not
+    fn san_moves(&self, parts: &SanParts) -> (PieceType, Vec<Move>) {
+        match *parts {
+            SanParts::Castling(_) => {
+                let king = self.king_square(self.active_color);
+                let moves = [king >> 2, king << 2]
+                    .into_iter()
+                    .map(|target| Move::new(PieceType::King, king, target))
+                    .filter(|chess_move| king != 0 && parts.matches(chess_move))
+                    .collect();
+                (PieceType::King, moves)
+            }
+            SanParts::Move { piece_type, target, promotion, file, rank } => {
+                // A pawn move without a file is a push on the file of the target
+                let parts = match (piece_type, file) {
+                    (PieceType::Pawn, None) => SanParts::Move { piece_type, target, promotion, file: Some(square_to_xy(target).0), rank },
+                    _ => parts.clone(),
+                };
+                let moves = squares(self.get_piece_info(piece_type, self.active_color).positions)
+                    .map(|from| Move { piece_type, old_position: from, new_position: target, promotion })
+                    .filter(|chess_move| parts.matches(chess_move))
+                    .collect();
+                (piece_type, moves)
+            }
+        }
+    }
+
+    fn validate_pawn_move(&self, chess_move: &Move) -> Result<(), IllegalMove> {
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        let occupancy = self.occupancy();
+        let (push, start_rank, attacks) = match self.active_color {
+            Color::White => (from << 8, RANK_TWO, white_pawn_attacks(from, !0)),
+            Color::Black => (from >> 8, RANK_SEVEN, black_pawn_attacks(from, !0)),
+        };
+        let double_push = match self.active_color {
+            Color::White => push << 8,
+            Color::Black => push >> 8,
+        };
+
+        if to == push || (to == double_push && from & start_rank != 0) {
+            let blockers = (push | to) & occupancy;
+            if blockers != 0 {
+                return Err(IllegalMove::PathBlocked { square: nearest_square(blockers, from) });
+            }
+        } else if to & attacks == 0 || (self.pieces_of(!self.active_color) & to == 0 && to != self.en_passant) {
+            // Pawns only move diagonally to capture
+            return Err(IllegalMove::CannotMoveThatWay { piece_type: PieceType::Pawn, square: from });
+        }
+
+        let promotes = to & (RANK_ONE | RANK_EIGHT) != 0;
+        match chess_move.promotion {
+            None if promotes => Err(IllegalMove::PromotionMissing),
+            Some(promotion) if !promotes || !PROMOTION_PIECES.contains(&promotion) => Err(IllegalMove::InvalidPromotion(promotion)),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_castling(&self, chess_move: &Move) -> Result<(), IllegalMove> {
+        let color = self.active_color;
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        let king = match color {
+            Color::White => WHITE_KING_START,
+            Color::Black => BLACK_KING_START,
+        };
+        if from != king {
+            return Err(IllegalMove::CannotMoveThatWay { piece_type: PieceType::King, square: from });
+        }
+        let castling = if to == king >> 2 { Castling::KingSide } else { Castling::QueenSide };
+        let (between, passed, rook) = match castling {
+            Castling::KingSide => (king >> 1 | king >> 2, king >> 1, king >> 3),
+            Castling::QueenSide => (king << 1 | king << 2 | king << 3, king << 1, king << 4),
+        };
+
+        if !self.has_castling_right(color, castling) || self.get_piece_info(PieceType::Rook, color).positions & rook == 0 {
+            return Err(IllegalMove::CastlingRightsLost(castling));
+        }
+        let blockers = between & self.occupancy();
+        if blockers != 0 {
+            return Err(IllegalMove::PathBlocked { square: nearest_square(blockers, king) });
+        }
+        if self.is_square_attacked(king, !color) {
+            return Err(IllegalMove::CastlingOutOfCheck);
+        }
+        if self.is_square_attacked(passed, !color) {
+            return Err(IllegalMove::CastlingThroughCheck { square: passed });
+        }
+        if self.is_square_attacked(to, !color) {
+            return Err(IllegalMove::CastlingIntoCheck { square: to });
+        }
+        Ok(())
+    }
+}
+
+// Of several occupied squares on a line from `from`, the one closest to it
+fn nearest_square(blockers: u64, from: u64) -> u64 {
+    let above = blockers & !(from | (from - 1));
+    if above != 0 {
+        above & above.wrapping_neg()
+    } else {
+        1 << (63 - blockers.leading_zeros())
+    }
+}
+
+// Orders the reasons from the moves of several pieces, the smallest is the most useful to report
+fn closeness(reason: &IllegalMove) -> u8 {
+    match reason {
+        IllegalMove::LeavesKingInCheck => 0,
+        IllegalMove::CastlingOutOfCheck
+        | IllegalMove::CastlingThroughCheck { .. }
+        | IllegalMove::CastlingIntoCheck { .. }
+        | IllegalMove::CastlingRightsLost(_) => 1,
+        IllegalMove::PromotionMissing | IllegalMove::InvalidPromotion(_) => 2,
+        IllegalMove::PathBlocked { .. } => 3,
+        IllegalMove::OwnPieceOnTarget { .. } => 4,
+        _ => 5,
+    }
+}
+
+fn piece_name(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::Pawn => "pawn",
+        PieceType::Knight => "knight",
+        PieceType::Bishop => "bishop",
+        PieceType::Rook => "rook",
+        PieceType::Queen => "queen",
+        PieceType::King => "king",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movesets::test_positions::{random_positions, KIWIPETE};
+    use crate::utils::board_utils::parse_square;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::san::move_to_san;
+
+    fn square(name: &str) -> u64 {
+        parse_square(name).unwrap()
+    }
+
+    #[test]
+    fn test_validate_uci() {
+        let chess_board = read_fen(KIWIPETE);
+        assert_eq!(Ok(Move::new(PieceType::Knight, square("e5"), square("f7"))), chess_board.validate_uci("e5f7"));
+        assert_eq!(Err(IllegalMove::InvalidNotation("e5f9".to_string())), chess_board.validate_uci("e5f9"));
+        assert_eq!(Err(IllegalMove::NoPiece { square: square("e3") }), chess_board.validate_uci("e3e4"));
+        assert_eq!(Err(IllegalMove::WrongColor { square: square("a6"), color: Color::Black }), chess_board.validate_uci("a6e2"));
+        assert_eq!(Err(IllegalMove::OwnPieceOnTarget { square: square("d2") }), chess_board.validate_uci("e1d2"));
+        assert_eq!(Err(IllegalMove::CannotMoveThatWay { piece_type: PieceType::Knight, square: square("e5") }), chess_board.validate_uci("e5e6"));
+        assert_eq!(Err(IllegalMove::CannotMoveThatWay { piece_type: PieceType::Pawn, square: square("a2") }), chess_board.validate_uci("a2b3"));
+        assert_eq!(Err(IllegalMove::NoPiece { square: square("c1") }), chess_board.validate_uci("c1h6"));
+        assert_eq!(Err(IllegalMove::PathBlocked { square: square("f6") }), chess_board.validate_uci("f3f7"));
+        assert_eq!(Err(IllegalMove::PathBlocked { square: square("h3") }), chess_board.validate_uci("h2h4"));
+        assert_eq!(Err(IllegalMove::InvalidPromotion(PieceType::Queen)), chess_board.validate_uci("a2a3q"));
+    }
+
+    #[test]
+    fn test_leaves_king_in_check() {
+        let chess_board = read_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1");
+        assert_eq!(Err(IllegalMove::LeavesKingInCheck), chess_board.validate_uci("e2c3"));
+        let rook_on_second_rank = read_fen("4k3/8/8/8/8/8/r7/4K3 w - - 0 1");
+        assert_eq!(Err(IllegalMove::LeavesKingInCheck), rook_on_second_rank.validate_uci("e1e2"));
+    }
+
+    #[test]
+    fn test_castling_reasons() {
+        let attacked = read_fen("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1");
+        assert_eq!(Err(IllegalMove::CastlingThroughCheck { square: square("f1") }), attacked.validate_uci("e1g1"));
+        let in_check = read_fen("r3k2r/8/8/8/8/8/4r3/R3K2R w KQkq - 0 1");
+        assert_eq!(Err(IllegalMove::CastlingOutOfCheck), in_check.validate_uci("e1c1"));
+        assert_eq!(Err(IllegalMove::CastlingOutOfCheck), in_check.validate_san("O-O"));
+        let into_check = read_fen("r3k2r/8/8/8/8/8/6r1/R3K2R w KQkq - 0 1");
+        assert_eq!(Err(IllegalMove::CastlingIntoCheck { square: square("g1") }), into_check.validate_uci("e1g1"));
+        // The square next to the rook is only crossed by the rook, it may be attacked
+        let b1_attacked = read_fen("r3k2r/8/8/8/8/8/1r6/R3K2R w KQkq - 0 1");
+        assert_eq!(Ok(Move::new(PieceType::King, square("e1"), square("c1"))), b1_attacked.validate_uci("e1c1"));
+        let c1_attacked = read_fen("r3k2r/8/8/8/8/8/2r5/R3K2R w KQkq - 0 1");
+        assert_eq!(Err(IllegalMove::CastlingIntoCheck { square: square("c1") }), c1_attacked.validate_san("O-O-O"));
+        let no_right = read_fen("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1");
+        assert_eq!(Err(IllegalMove::CastlingRightsLost(Castling::KingSide)), no_right.validate_uci("e1g1"));
+        let blocked = read_fen("r3k2r/8/8/8/8/8/8/RN2K2R w KQkq - 0 1");
+        assert_eq!(Err(IllegalMove::PathBlocked { square: square("b1") }), blocked.validate_uci("e1c1"));
+        assert_eq!(Ok(Move::new(PieceType::King, square("e1"), square("g1"))), blocked.validate_san("O-O"));
+        assert_eq!(Err(IllegalMove::PathBlocked { square: square("b1") }), blocked.validate_san("O-O-O"));
+    }
+
+    #[test]
+    fn test_promotion_reasons() {
+        let chess_board = read_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(Err(IllegalMove::PromotionMissing), chess_board.validate_uci("a7a8"));
+        assert_eq!(Err(IllegalMove::PromotionMissing), chess_board.validate_san("a8"));
+        assert_eq!(Err(IllegalMove::PromotionMissing), chess_board.validate_san("axb8"));
+        assert_eq!(Ok(Move::new_promotion(square("a7"), square("b8"), PieceType::Knight)), chess_board.validate_san("axb8=N"));
+    }
+
+    #[test]
+    fn test_validate_san() {
+        let chess_board = read_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1");
+        let Err(IllegalMove::AmbiguousSan(candidates)) = chess_board.validate_san("Rf1") else {
+            panic!("Rf1 should be ambiguous");
+        };
+        let mut names: Vec<String> = candidates.iter().map(|chess_move| move_to_san(&chess_board, chess_move)).collect();
+        names.sort();
+        assert_eq!(vec!["Raf1", "Rhf1"], names);
+        assert_eq!(Ok(Move::new(PieceType::Rook, square("a1"), square("f1"))), chess_board.validate_san("Raf1"));
+        assert_eq!(Err(IllegalMove::NoPieceOfType(PieceType::Knight)), chess_board.validate_san("Nf3"));
+        assert_eq!(Err(IllegalMove::InvalidNotation("Zf3".to_string())), chess_board.validate_san("Zf3"));
+        assert_eq!(Err(IllegalMove::CannotMoveThatWay { piece_type: PieceType::Rook, square: square("h1") }), chess_board.validate_san("Rb2"));
+    }
+
+    #[test]
+    fn test_display() {
+        let chess_board = read_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1");
+        assert_eq!("The rook on h1 can't move that way", chess_board.validate_san("Rb2").unwrap_err().to_string());
+        assert_eq!("The move is ambiguous, it could be h1f1, a1f1", chess_board.validate_san("Rf1").unwrap_err().to_string());
+        assert_eq!("The piece on e8 is Black, but it is White to move", chess_board.validate_uci("e8d8").unwrap_err().to_string());
+        let into_check = read_fen("r3k2r/8/8/8/8/8/6r1/R3K2R w KQkq - 0 1");
+        assert_eq!("The king can't castle into check on g1", into_check.validate_san("O-O").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_agrees_with_is_legal() {
+        for chess_board in random_positions(49, 3) {
+            for from in squares(chess_board.occupancy()) {
+                let (piece_type, _) = chess_board.piece_at(from).unwrap();
+                for to in squares(!0) {
+                    for promotion in [None, Some(PieceType::Queen)] {
+                        let chess_move = Move { piece_type, old_position: from, new_position: to, promotion };
+                        let result = chess_board.validate_move(&chess_move);
+                        assert_eq!(chess_board.is_legal(&chess_move), result.is_ok(), "{:?} {:?}", chess_move, result);
+                    }
+                }
+            }
+        }
+    }
+}
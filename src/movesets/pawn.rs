@@ -13,19 +13,19 @@ const FORWARD_LEFT: u8 = 9;
 // ##################################
 
 // A function to generate single pawn pushes for white pawns
-fn white_pawn_single_push(pawn_positions: u64, empty_squares: u64) -> u64 {
+pub(crate) fn white_pawn_single_push(pawn_positions: u64, empty_squares: u64) -> u64 {
     (pawn_positions << FORWARD) & empty_squares
 }
 
 // A function to generate double pawn pushes for white pawns. 
 // First do a single push, then again do a single push and check against rank 4
-fn white_pawn_double_push(pawns: u64, empty_squares: u64) -> u64 {
+pub(crate) fn white_pawn_double_push(pawns: u64, empty_squares: u64) -> u64 {
     let single_push_squares = white_pawn_single_push(pawns, empty_squares);
     let double_push_sqares = white_pawn_single_push(single_push_squares, empty_squares);
     double_push_sqares & RANK_FOUR
 }
 
-fn white_pawn_attacks(pawn_positions: u64, opponent_pieces: u64) -> u64 {
+pub(crate) fn white_pawn_attacks(pawn_positions: u64, opponent_pieces: u64) -> u64 {
     // Shift the pawn bitboard one square to the left, then one square up.
     // H file is excluded so pawns are not shifted off the board
     let left_attacks = (pawn_positions << FORWARD_RIGHT ) & !A_FILE;
@@ -57,19 +57,19 @@ fn white_en_passant_calculation(last_move: &Move, pawn_positions: u64) -> u64 {
 // ##################################
 
 // A function to generate single pawn pushes for white pawns
-fn black_pawn_single_push(pawn_positions: u64, empty_squares: u64) -> u64 {
+pub(crate) fn black_pawn_single_push(pawn_positions: u64, empty_squares: u64) -> u64 {
     (pawn_positions >> FORWARD) & empty_squares
 }
 
 // A function to generate double pawn pushes for white pawns. 
 // First do a single push, then again do a single push and check against rank 5
-fn black_pawn_double_push(pawns: u64, empty_squares: u64) -> u64 {
+pub(crate) fn black_pawn_double_push(pawns: u64, empty_squares: u64) -> u64 {
     let single_push_squares = black_pawn_single_push(pawns, empty_squares);
     let double_push_sqares = black_pawn_single_push(single_push_squares, empty_squares);
     double_push_sqares & RANK_FIVE
 }
 
-fn black_pawn_attacks(pawn_positions: u64, opponent_pieces: u64) -> u64 {
+pub(crate) fn black_pawn_attacks(pawn_positions: u64, opponent_pieces: u64) -> u64 {
     // Shift the pawn bitboard one square to the right, then one square up.
     // A file is excluded so pawns are not shifted off the board
     let left_attacks = (pawn_positions >> FORWARD_LEFT) & !A_FILE;
@@ -100,20 +100,15 @@ fn black_en_passant_calculation(last_move: &Move, pawn_positions: u64) -> u64 {
 // ###################################
 
 // A function to generate all moves for pawns depending on color
-fn get_pawn_moves(pawn_positions: u64, empty_squares: u64, color: Color) -> u64 {
-    let mut single_pushes = 0;
-    let mut double_pushes = 0;
+pub(crate) fn get_pawn_moves(pawn_positions: u64, empty_squares: u64, color: Color) -> u64 {
     if color == Color::White  {
-        single_pushes = white_pawn_single_push(pawn_positions, empty_squares);
-        double_pushes = white_pawn_double_push(pawn_positions, empty_squares);
+        white_pawn_single_push(pawn_positions, empty_squares) | white_pawn_double_push(pawn_positions, empty_squares)
     } else {
-        single_pushes = black_pawn_single_push(pawn_positions, empty_squares);
-        double_pushes = black_pawn_double_push(pawn_positions, empty_squares);
+        black_pawn_single_push(pawn_positions, empty_squares) | black_pawn_double_push(pawn_positions, empty_squares)
     }
-    single_pushes | double_pushes
 }
 // get all possible attacks for the pawns
-fn get_pawn_attack_set(last_move: &Move, pawn_positions: u64, opponent_pieces: u64, color: Color) -> u64{
+pub(crate) fn get_pawn_attack_set(last_move: &Move, pawn_positions: u64, opponent_pieces: u64, color: Color) -> u64{
     if color == Color::White{
         white_pawn_attacks(pawn_positions, opponent_pieces) | white_en_passant_calculation(last_move, pawn_positions)
     } else{
@@ -122,6 +117,7 @@ fn get_pawn_attack_set(last_move: &Move, pawn_positions: u64, opponent_pieces: u
 }
 
 
+#[cfg(test)]
 mod tests {
     use crate::chess::piece::PieceType;
     use super::*;
@@ -246,7 +242,8 @@ mod tests {
         let last_move = Move {
                 piece_type:PieceType::Pawn,
                 old_position: 0b00000000_00010000_00000000_00000000_00000000_00000000_00000000_00000000,
-                new_position: 0b00000000_00000000_00000000_00010000_00000000_00000000_00000000_00000000
+                new_position: 0b00000000_00000000_00000000_00010000_00000000_00000000_00000000_00000000,
+                promotion: None
             };
         // En passant is possible
         let expected_result = 0b00000000_00000000_00010000_00000000_00000000_00000000_00000000_00000000;
@@ -374,7 +371,8 @@ mod tests {
         let last_move = Move {
                 piece_type:PieceType::Pawn,
                 old_position: 0b00000000_00000000_00000000_00000000_00000000_00000000_00010000_00000000,
-                new_position: 0b00000000_00000000_00000000_00000000_00010000_00000000_00000000_00000000
+                new_position: 0b00000000_00000000_00000000_00000000_00010000_00000000_00000000_00000000,
+                promotion: None
             };
         // En passant is possible
         let expected_result = 0b00000000_00000000_00000000_00000000_00000000_00010000_00000000_00000000;
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::Move;
+use crate::movesets::move_generator::GenerationMode;
+use crate::movesets::move_list::MoveList;
+use crate::utils::packed_position::encode_position;
+
+// Perft counts the leaf nodes of the game tree up to a depth. Comparing the counts with published
+// numbers is the standard way to find bugs in move generation, and divide shows which root move
+// leads to a different count.
+
+// The packed position without the move clocks, which do not change the moves
+const POSITION_KEY_SIZE: usize = 26;
+
+type PerftTable = HashMap<([u8; POSITION_KEY_SIZE], u32), u64>;
+
+impl ChessBoard {
+    /// Counts the positions reached after `depth` half moves.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut moves = MoveList::new();
+        self.generate_legal_moves(GenerationMode::All, &mut moves);
+        // At the last ply the moves only need to be counted
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves.iter().map(|chess_move| self.after(chess_move).perft(depth - 1)).sum()
+    }
+
+    /// Returns every legal move with the number of positions it leads to after `depth` half moves,
+    /// including the move itself.
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        self.legal_moves()
+            .into_iter()
+            .map(|chess_move| {
+                let nodes = self.after(&chess_move).perft(depth - 1);
+                (chess_move, nodes)
+            })
+            .collect()
+    }
+
+    /// Like `perft`, but positions that were already counted at the same depth are looked up in a
+    /// table instead of being searched again. Transpositions make this much faster for deep runs.
+    pub fn perft_hashed(&self, depth: u32) -> u64 {
+        self.perft_with_table(depth, &mut HashMap::new())
+    }
+
+    /// Like `perft_hashed`, with the moves at the root counted on all cores.
+    pub fn perft_parallel(&self, depth: u32) -> u64 {
+        if depth <= 1 {
+            return self.perft(depth);
+        }
+        self.legal_moves()
+            .into_par_iter()
+            .map(|chess_move| self.after(&chess_move).perft_hashed(depth - 1))
+            .sum()
+    }
+
+    fn perft_with_table(&self, depth: u32, table: &mut PerftTable) -> u64 {
+        if depth <= 1 {
+            return self.perft(depth);
+        }
+        let key = (position_key(self), depth);
+        if let Some(&nodes) = table.get(&key) {
+            return nodes;
+        }
+        let mut moves = MoveList::new();
+        self.generate_legal_moves(GenerationMode::All, &mut moves);
+        let nodes = moves.iter().map(|chess_move| self.after(chess_move).perft_with_table(depth - 1, table)).sum();
+        table.insert(key, nodes);
+        nodes
+    }
+
+    fn after(&self, chess_move: &Move) -> ChessBoard {
+        let mut next = self.clone();
+        next.make_move(chess_move);
+        next
+    }
+}
+
+fn position_key(chess_board: &ChessBoard) -> [u8; POSITION_KEY_SIZE] {
+    let packed = encode_position(chess_board).expect("Positions reached by legal moves can be packed");
+    let mut key = [0; POSITION_KEY_SIZE];
+    key.copy_from_slice(&packed[..POSITION_KEY_SIZE]);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divide() {
+        let chess_board = ChessBoard::new(false);
+        let divide = chess_board.divide(2);
+        assert_eq!(20, divide.len());
+        assert!(divide.iter().all(|(_, nodes)| *nodes == 20));
+        assert_eq!(chess_board.perft(2), divide.iter().map(|(_, nodes)| nodes).sum::<u64>());
+        assert!(chess_board.divide(0).is_empty());
+    }
+
+    #[test]
+    fn test_perft_variants_agree() {
+        let chess_board = ChessBoard::new(false);
+        assert_eq!(1, chess_board.perft(0));
+        assert_eq!(8902, chess_board.perft(3));
+        assert_eq!(8902, chess_board.perft_hashed(3));
+        assert_eq!(8902, chess_board.perft_parallel(3));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::movesets::magic_bitboards::{BLOCKERBOARDS_BISHOP, BLOCKERBOARDS_ROOK, BLOCKERMASKS_BISHOP, BLOCKERMASKS_ROOK, MOVEBOARDS_BISHOP, MOVEBOARDS_ROOK};
+use crate::movesets::sliders::{SliderAttacks, SliderFunctions};
+use lazy_static::lazy_static;
+
+// Slider attacks indexed with PEXT: the bits of the occupancy under the blockermask, packed together,
+// are a perfect index without any magic number. The BMI2 instruction is used when the processor has
+// it, otherwise the same index is computed bit by bit.
+
+lazy_static! {
+    static ref PEXT_TABLE_ROOK: PextTable = PextTable::new(&BLOCKERMASKS_ROOK, &BLOCKERBOARDS_ROOK, &MOVEBOARDS_ROOK);
+    static ref PEXT_TABLE_BISHOP: PextTable = PextTable::new(&BLOCKERMASKS_BISHOP, &BLOCKERBOARDS_BISHOP, &MOVEBOARDS_BISHOP);
+}
+
+// One shared table like the fancy magics, every square starts at its own offset
+struct PextTable {
+    blockermasks: [u64; 64],
+    offsets: [usize; 64],
+    moveboards: Vec<u64>,
+}
+
+impl PextTable {
+    fn new(blockermasks: &[u64; 64], blockerboards: &[Vec<u64>], moveboards: &[Vec<u64>]) -> PextTable {
+        let mut offsets = [0; 64];
+        let mut table = Vec::new();
+        for i in 0..64 {
+            let entries = 1 << blockermasks[i].count_ones();
+            offsets[i] = table.len();
+            table.resize(table.len() + entries, 0);
+            for (blockerboard, moveboard) in blockerboards[i][..entries].iter().zip(&moveboards[i]) {
+                table[offsets[i] + pext_software(*blockerboard, blockermasks[i]) as usize] = *moveboard;
+            }
+        }
+        PextTable { blockermasks: *blockermasks, offsets, moveboards: table }
+    }
+
+    #[inline]
+    fn moveboard(&self, index: usize, occupancy: u64, use_bmi2: bool) -> u64 {
+        self.moveboards[self.offsets[index] + pext(occupancy, self.blockermasks[index], use_bmi2) as usize]
+    }
+}
+
+/// PEXT indexed look-up tables, with the BMI2 instruction if available.
+pub struct PextAttacks {
+    use_bmi2: bool,
+}
+
+impl PextAttacks {
+    /// Uses the BMI2 instruction if the processor supports it.
+    #[inline]
+    pub fn new() -> PextAttacks {
+        PextAttacks { use_bmi2: has_bmi2() }
+    }
+
+    /// Always uses the portable implementation.
+    pub fn software() -> PextAttacks {
+        PextAttacks { use_bmi2: false }
+    }
+}
+
+impl PextAttacks {
+    // The functions for SliderBackend::Pext, with the BMI2 check done once here instead of per look-up
+    pub(crate) fn functions() -> SliderFunctions {
+        if has_bmi2() {
+            SliderFunctions {
+                rook: |index, occupancy| PEXT_TABLE_ROOK.moveboard(index, occupancy, true),
+                bishop: |index, occupancy| PEXT_TABLE_BISHOP.moveboard(index, occupancy, true),
+            }
+        } else {
+            SliderFunctions {
+                rook: |index, occupancy| PEXT_TABLE_ROOK.moveboard(index, occupancy, false),
+                bishop: |index, occupancy| PEXT_TABLE_BISHOP.moveboard(index, occupancy, false),
+            }
+        }
+    }
+}
+
+impl Default for PextAttacks {
+    fn default() -> Self {
+        PextAttacks::new()
+    }
+}
+
+impl SliderAttacks for PextAttacks {
+    #[inline]
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        PEXT_TABLE_ROOK.moveboard(index, occupancy, self.use_bmi2)
+    }
+
+    #[inline]
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        PEXT_TABLE_BISHOP.moveboard(index, occupancy, self.use_bmi2)
+    }
+}
+
+/// True if the processor has the BMI2 PEXT instruction. The detection is cached by the standard library.
+pub fn has_bmi2() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        is_x86_feature_detected!("bmi2")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+#[inline]
+fn pext(value: u64, mask: u64, use_bmi2: bool) -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    if use_bmi2 {
+        // Safe because use_bmi2 is only set when the processor supports BMI2
+        return unsafe { pext_bmi2(value, mask) };
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = use_bmi2;
+    pext_software(value, mask)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn pext_bmi2(value: u64, mask: u64) -> u64 {
+    std::arch::x86_64::_pext_u64(value, mask)
+}
+
+// Packs the bits of value under the mask into the lowest bits, in the order of the mask
+fn pext_software(value: u64, mask: u64) -> u64 {
+    let mut result = 0;
+    let mut mask = mask;
+    let mut bit = 1;
+    while mask != 0 {
+        let lowest = mask & mask.wrapping_neg();
+        if value & lowest != 0 {
+            result |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pext_software() {
+        assert_eq!(0b1011, pext_software(0b1001_0001, 0b1011_0001));
+        assert_eq!(0, pext_software(u64::MAX, 0));
+        assert_eq!(u64::MAX, pext_software(u64::MAX, u64::MAX));
+        if has_bmi2() {
+            for (value, mask) in [(0x1234_5678_9abc_def0, 0x00ff_ff00_0f0f_f0f0), (u64::MAX, 0x8000_0000_0000_0001)] {
+                assert_eq!(pext_software(value, mask), pext(value, mask, true));
+            }
+        }
+    }
+}
@@ -1,10 +1,10 @@
-use crate::movesets::bishop::*;
-use crate::movesets::rook::*;
+use crate::movesets::sliders::{slider_functions, SliderAttacks};
 
 pub(crate) fn queen_move(square : u64, occupancy : u64 ) -> u64{
-    bishop_move(square, occupancy) | rook_move(square, occupancy)
+    slider_functions().queen_attacks(square.trailing_zeros() as usize, occupancy)
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
     use crate::movesets::magic_bitboards::initialize;
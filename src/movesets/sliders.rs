@@ -0,0 +1,221 @@
+use std::sync::OnceLock;
+
+use lazy_static::lazy_static;
+
+use crate::movesets::hyperbola_quintessence::HyperbolaQuintessenceAttacks;
+use crate::movesets::kogge_stone::KoggeStoneAttacks;
+use crate::movesets::magic_bitboards::{generate_magic_tables, BLOCKERBOARDS_BISHOP, BLOCKERBOARDS_ROOK, BLOCKERMASKS_BISHOP, BLOCKERMASKS_ROOK, FANCY_MAGICS_BISHOP, FANCY_MAGICS_ROOK, MOVEBOARDS_BISHOP, MOVEBOARDS_ROOK};
+use crate::movesets::magic_numbers::{BISHOP_INDEX_BITS, BISHOP_MAGICS, ROOK_INDEX_BITS, ROOK_MAGICS};
+use crate::movesets::pext::PextAttacks;
+
+// The attacks of rooks, bishops and queens can be computed in several ways. rook_move, bishop_move
+// and queen_move use the backend chosen with set_slider_backend, fancy magics unless changed. The
+// backend is resolved to plain function pointers once and can not be switched afterwards, the
+// benchmark and the tests use the backends directly.
+
+/// Computes the squares a slider on the square with the given index attacks for an occupancy.
+/// The attacks include the first blocker in every direction, whatever its color.
+pub trait SliderAttacks {
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64;
+
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64;
+
+    fn queen_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        self.rook_attacks(index, occupancy) | self.bishop_attacks(index, occupancy)
+    }
+}
+
+/// The available slider backends.
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+pub enum SliderBackend {
+    PlainMagics,
+    FancyMagics,
+    Pext,
+    KoggeStone,
+    HyperbolaQuintessence,
+}
+
+impl SliderBackend {
+    pub const ALL: [SliderBackend; 5] = [
+        SliderBackend::PlainMagics,
+        SliderBackend::FancyMagics,
+        SliderBackend::Pext,
+        SliderBackend::KoggeStone,
+        SliderBackend::HyperbolaQuintessence,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SliderBackend::PlainMagics => "plain magics",
+            SliderBackend::FancyMagics => "fancy magics",
+            SliderBackend::Pext => "pext",
+            SliderBackend::KoggeStone => "kogge-stone",
+            SliderBackend::HyperbolaQuintessence => "hyperbola quintessence",
+        }
+    }
+
+    /// Resolves the attack functions of the backend once, including the BMI2 check for PEXT.
+    pub fn functions(self) -> SliderFunctions {
+        match self {
+            SliderBackend::PlainMagics => SliderFunctions {
+                rook: |index, occupancy| PlainMagicAttacks.rook_attacks(index, occupancy),
+                bishop: |index, occupancy| PlainMagicAttacks.bishop_attacks(index, occupancy),
+            },
+            SliderBackend::FancyMagics => SliderFunctions {
+                rook: |index, occupancy| FancyMagicAttacks.rook_attacks(index, occupancy),
+                bishop: |index, occupancy| FancyMagicAttacks.bishop_attacks(index, occupancy),
+            },
+            SliderBackend::Pext => PextAttacks::functions(),
+            SliderBackend::KoggeStone => SliderFunctions {
+                rook: |index, occupancy| KoggeStoneAttacks.rook_attacks(index, occupancy),
+                bishop: |index, occupancy| KoggeStoneAttacks.bishop_attacks(index, occupancy),
+            },
+            SliderBackend::HyperbolaQuintessence => SliderFunctions {
+                rook: |index, occupancy| HyperbolaQuintessenceAttacks.rook_attacks(index, occupancy),
+                bishop: |index, occupancy| HyperbolaQuintessenceAttacks.bishop_attacks(index, occupancy),
+            },
+        }
+    }
+}
+
+/// The rook and bishop attack functions of a backend, so a look-up is a single call without
+/// checking which backend is in use.
+#[derive(Clone, Copy)]
+pub struct SliderFunctions {
+    pub rook: fn(usize, u64) -> u64,
+    pub bishop: fn(usize, u64) -> u64,
+}
+
+impl SliderAttacks for SliderFunctions {
+    #[inline]
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        (self.rook)(index, occupancy)
+    }
+
+    #[inline]
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        (self.bishop)(index, occupancy)
+    }
+}
+
+static SLIDER_FUNCTIONS: OnceLock<SliderFunctions> = OnceLock::new();
+
+/// Chooses the backend used by rook_move, bishop_move and queen_move for the whole program. The
+/// backend can only be chosen once: the first slider look-up fixes it, and that includes look-ups
+/// made to build internal tables such as the squares between two squares. Call this at the start
+/// of the program, before any moves are generated or attacks are queried. Afterwards it returns
+/// an error, also for the backend that is already in use. Use `SliderBackend::functions` to
+/// compare backends within one program.
+pub fn set_slider_backend(backend: SliderBackend) -> Result<(), String> {
+    SLIDER_FUNCTIONS
+        .set(backend.functions())
+        .map_err(|_| format!("Can not switch to {}, the slider backend is already in use", backend.name()))
+}
+
+/// The attack functions used by rook_move, bishop_move and queen_move, fancy magics unless another
+/// backend was set before the first look-up.
+#[inline]
+pub(crate) fn slider_functions() -> &'static SliderFunctions {
+    SLIDER_FUNCTIONS.get_or_init(|| SliderBackend::FancyMagics.functions())
+}
+
+lazy_static! {
+    // Only built when the plain magics are used, e.g. by the benchmark or the tests
+    static ref PLAIN_MAGICS_ROOK: Vec<Vec<Option<u64>>> = generate_magic_tables(&ROOK_MAGICS, &ROOK_INDEX_BITS, &BLOCKERBOARDS_ROOK, &MOVEBOARDS_ROOK, &BLOCKERMASKS_ROOK);
+    static ref PLAIN_MAGICS_BISHOP: Vec<Vec<Option<u64>>> = generate_magic_tables(&BISHOP_MAGICS, &BISHOP_INDEX_BITS, &BLOCKERBOARDS_BISHOP, &MOVEBOARDS_BISHOP, &BLOCKERMASKS_BISHOP);
+}
+
+/// Plain magic bitboards with a table of optional moveboards per square.
+pub struct PlainMagicAttacks;
+
+impl SliderAttacks for PlainMagicAttacks {
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        let blockerboard = occupancy & BLOCKERMASKS_ROOK[index];
+        let magic_index = (blockerboard.wrapping_mul(ROOK_MAGICS[index])) >> (64 - ROOK_INDEX_BITS[index]);
+        PLAIN_MAGICS_ROOK[index][magic_index as usize].unwrap()
+    }
+
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        let blockerboard = occupancy & BLOCKERMASKS_BISHOP[index];
+        let magic_index = (blockerboard.wrapping_mul(BISHOP_MAGICS[index])) >> (64 - BISHOP_INDEX_BITS[index]);
+        PLAIN_MAGICS_BISHOP[index][magic_index as usize].unwrap()
+    }
+}
+
+/// Fancy magic bitboards with one shared table, see FancyMagics.
+pub struct FancyMagicAttacks;
+
+impl SliderAttacks for FancyMagicAttacks {
+    #[inline]
+    fn rook_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        FANCY_MAGICS_ROOK.moveboard(index, occupancy)
+    }
+
+    #[inline]
+    fn bishop_attacks(&self, index: usize, occupancy: u64) -> u64 {
+        FANCY_MAGICS_BISHOP.moveboard(index, occupancy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every backend as its own instance and as resolved functions. The backend of rook_move,
+    // bishop_move and queen_move is never changed here, as other tests run in parallel.
+    fn all_backends() -> Vec<(&'static str, Box<dyn SliderAttacks>)> {
+        let mut backends: Vec<(&'static str, Box<dyn SliderAttacks>)> = vec![
+            ("plain magics", Box::new(PlainMagicAttacks)),
+            ("fancy magics", Box::new(FancyMagicAttacks)),
+            ("pext", Box::new(PextAttacks::new())),
+            ("software pext", Box::new(PextAttacks::software())),
+            ("kogge-stone", Box::new(KoggeStoneAttacks)),
+            ("hyperbola quintessence", Box::new(HyperbolaQuintessenceAttacks)),
+        ];
+        for backend in SliderBackend::ALL {
+            backends.push((backend.name(), Box::new(backend.functions())));
+        }
+        backends
+    }
+
+    #[test]
+    fn test_slider_backend_is_fixed_after_first_look_up() {
+        // Fancy magics are the default, so this does not change the backend of other tests
+        slider_functions();
+        assert!(set_slider_backend(SliderBackend::FancyMagics).is_err());
+    }
+
+    #[test]
+    fn test_backends_agree_on_all_blockerboards() {
+        let backends = all_backends();
+        for index in 0..64 {
+            let rook_entries = 1 << BLOCKERMASKS_ROOK[index].count_ones();
+            for (blockerboard, moveboard) in BLOCKERBOARDS_ROOK[index][..rook_entries].iter().zip(&MOVEBOARDS_ROOK[index]) {
+                for (name, backend) in &backends {
+                    assert_eq!(*moveboard, backend.rook_attacks(index, *blockerboard), "{} rook on {} with {:#x}", name, index, blockerboard);
+                }
+            }
+            let bishop_entries = 1 << BLOCKERMASKS_BISHOP[index].count_ones();
+            for (blockerboard, moveboard) in BLOCKERBOARDS_BISHOP[index][..bishop_entries].iter().zip(&MOVEBOARDS_BISHOP[index]) {
+                for (name, backend) in &backends {
+                    assert_eq!(*moveboard, backend.bishop_attacks(index, *blockerboard), "{} bishop on {} with {:#x}", name, index, blockerboard);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_backends_ignore_pieces_outside_the_lines() {
+        // The square itself and pieces behind the edge squares do not change the attacks
+        let backends = all_backends();
+        for index in 0..64 {
+            let occupancy = 1 << index | 0x8100_0000_0000_0081 | 0x0000_1800_0018_0000;
+            let (_, reference) = &backends[0];
+            for (name, backend) in &backends {
+                assert_eq!(reference.rook_attacks(index, occupancy), backend.rook_attacks(index, occupancy), "{} rook on {}", name, index);
+                assert_eq!(reference.bishop_attacks(index, occupancy), backend.bishop_attacks(index, occupancy), "{} bishop on {}", name, index);
+                assert_eq!(reference.queen_attacks(index, occupancy), backend.queen_attacks(index, occupancy), "{} queen on {}", name, index);
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::chess::chess_board::ChessBoard;
+use crate::utils::fen_reader::read_fen;
+use crate::utils::random_positions::random_playout;
+
+// Positions shared by the move generation tests. Most are from the perft suites of the Chess
+// Programming Wiki, the others add a double check, a pawn check that en passant can take and a
+// discovered check by castling.
+
+pub const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+pub const PERFT_FENS: [&str; 10] = [
+    KIWIPETE,
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    "4k3/8/5N2/8/1b6/8/8/4R2K b - - 0 1",
+    "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+    "4k3/8/8/8/1q6/8/8/1N2K2R w K - 0 1",
+    "3k4/8/8/8/8/8/3N4/R3K2R w KQ - 0 1",
+];
+
+/// The perft positions and every position of a random game of up to `plies` half moves from each.
+/// The same seed always gives the same positions.
+pub fn random_positions(seed: u64, plies: usize) -> Vec<ChessBoard> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    PERFT_FENS.iter().flat_map(|fen| random_playout(&read_fen(fen), plies, &mut rng)).collect()
+}
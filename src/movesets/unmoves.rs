@@ -0,0 +1,299 @@
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::{ChessBoard, BLACK_KING_START, WHITE_KING_START};
+use crate::chess::chess_move::{castling_rook_squares, Move};
+use crate::chess::piece::{Color, PieceType};
+use crate::movesets::move_generator::{piece_targets, PROMOTION_PIECES};
+use crate::movesets::pawn::{black_pawn_attacks, white_pawn_attacks};
+use crate::utils::board_utils::{squares, RANK_EIGHT, RANK_ONE, RANK_SIX, RANK_THREE};
+use crate::utils::position_validator::validate_position;
+
+// Retrograde move generation: the moves that could have led to a position, for tablebase
+// generation, proof games and retro analysis. The pieces move backwards with the same attack
+// functions as forwards, since a piece reaches the squares it could have come from.
+//
+// What happened before a position is not fully known. The earlier position keeps the castling rights
+// of this one, gains only the right a castling move used, and has no en passant square unless the
+// move was an en passant capture.
+
+const UNCAPTURE_PIECES: [PieceType; 5] = [PieceType::Pawn, PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen];
+
+/// A move taken back, together with what it captured.
+#[derive(PartialEq, Debug, Eq, Hash, Clone)]
+pub struct Unmove {
+    // The move that leads from the earlier position to this one
+    pub chess_move: Move,
+    // The piece of the active color that was captured, put back on the target square or for en
+    // passant behind it
+    pub uncapture: Option<PieceType>,
+    pub en_passant: bool,
+}
+
+impl ChessBoard {
+    /// Lists the moves of the color that just moved which lead to this position from a legal one,
+    /// including uncaptures of every piece type, un-promotions and un-castling.
+    pub fn unmoves(&self) -> Vec<Unmove> {
+        let mut unmoves = Vec::new();
+        let mover = !self.active_color;
+
+        // A double push always leaves an en passant square behind, and only a double push does
+        if self.en_passant != 0 {
+            let chess_move = Move::new(PieceType::Pawn, behind(self.en_passant, mover), behind(self.en_passant, !mover));
+            self.add_unmoves(&mut unmoves, chess_move, false);
+            return self.legal_unmoves(unmoves);
+        }
+
+        let empty_squares = !self.occupancy();
+        for piece_type in [PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen, PieceType::King] {
+            for to in squares(self.get_piece_info(piece_type, mover).positions) {
+                for from in squares(piece_targets(piece_type, to, self.occupancy()) & empty_squares) {
+                    self.add_unmoves(&mut unmoves, Move::new(piece_type, from, to), true);
+                }
+            }
+        }
+        self.add_pawn_unmoves(&mut unmoves);
+        self.add_uncastling(&mut unmoves);
+        self.legal_unmoves(unmoves)
+    }
+
+    /// Returns the position before the unmove, with the other color to move.
+    pub fn unmake_move(&self, unmove: &Unmove) -> ChessBoard {
+        let mover = !self.active_color;
+        let chess_move = &unmove.chess_move;
+        let (from, to) = (chess_move.old_position, chess_move.new_position);
+        let mut previous = self.clone();
+        previous.move_history.clear();
+
+        let arrived = chess_move.promotion.unwrap_or(chess_move.piece_type);
+        previous.get_mutable_piece_info(arrived, mover).positions &= !to;
+        previous.get_mutable_piece_info(chess_move.piece_type, mover).positions |= from;
+
+        if let Some(captured) = unmove.uncapture {
+            let square = if unmove.en_passant { behind(to, mover) } else { to };
+            previous.get_mutable_piece_info(captured, self.active_color).positions |= square;
+        }
+
+        if chess_move.is_castling() {
+            let (rook_from, rook_to) = castling_rook_squares(to, from);
+            let castling = if to < from { Castling::KingSide } else { Castling::QueenSide };
+            let rooks = previous.get_mutable_piece_info(PieceType::Rook, mover);
+            rooks.positions = (rooks.positions & !rook_to) | rook_from;
+            previous.set_castling(mover, castling);
+        }
+
+        previous.en_passant = if unmove.en_passant { to } else { 0 };
+        // The clock before a capture or pawn move is not known
+        previous.halfmove_clock = if chess_move.piece_type == PieceType::Pawn || unmove.uncapture.is_some() { 0 } else { self.halfmove_clock.saturating_sub(1) };
+        if mover == Color::Black {
+            previous.fullmove_number = self.fullmove_number.saturating_sub(1).max(1);
+        }
+        previous.active_color = mover;
+        previous
+    }
+
+    // Adds the unmove without a capture and, if `uncaptures` is set, with every piece the active
+    // color could have lost on the target square
+    fn add_unmoves(&self, unmoves: &mut Vec<Unmove>, chess_move: Move, uncaptures: bool) {
+        if uncaptures {
+            self.add_uncaptures(unmoves, &chess_move);
+        }
+        unmoves.push(Unmove { chess_move, uncapture: None, en_passant: false });
+    }
+
+    fn add_uncaptures(&self, unmoves: &mut Vec<Unmove>, chess_move: &Move) {
+        for captured in UNCAPTURE_PIECES {
+            if captured == PieceType::Pawn && chess_move.new_position & (RANK_ONE | RANK_EIGHT) != 0 {
+                continue;
+            }
+            unmoves.push(Unmove { chess_move: chess_move.clone(), uncapture: Some(captured), en_passant: false });
+        }
+    }
+
+    fn add_pawn_unmoves(&self, unmoves: &mut Vec<Unmove>) {
+        let mover = !self.active_color;
+        // A pawn never stands on the first or last rank, so it can't have come from there
+        let origins = !self.occupancy() & !(RANK_ONE | RANK_EIGHT);
+        let (last_rank, en_passant_rank) = match mover {
+            Color::White => (RANK_EIGHT, RANK_SIX),
+            Color::Black => (RANK_ONE, RANK_THREE),
+        };
+        // The squares a pawn captured on `square` from are the ones a pawn of the other color on
+        // `square` would attack
+        let capture_origins = |square: u64| match mover {
+            Color::White => black_pawn_attacks(square, origins),
+            Color::Black => white_pawn_attacks(square, origins),
+        };
+
+        for to in squares(self.get_piece_info(PieceType::Pawn, mover).positions) {
+            // Double pushes were handled with the en passant square
+            if behind(to, mover) & origins != 0 {
+                self.add_unmoves(unmoves, Move::new(PieceType::Pawn, behind(to, mover), to), false);
+            }
+            for from in squares(capture_origins(to)) {
+                let capture = Move::new(PieceType::Pawn, from, to);
+                self.add_uncaptures(unmoves, &capture);
+                // The pawn taken en passant passed the target square, coming from in front of it
+                let passed = behind(to, mover) | behind(to, !mover);
+                if to & en_passant_rank != 0 && self.occupancy() & passed == 0 {
+                    unmoves.push(Unmove { chess_move: capture, uncapture: Some(PieceType::Pawn), en_passant: true });
+                }
+            }
+        }
+
+        for promotion in PROMOTION_PIECES {
+            for to in squares(self.get_piece_info(promotion, mover).positions & last_rank) {
+                if behind(to, mover) & origins != 0 {
+                    self.add_unmoves(unmoves, Move::new_promotion(behind(to, mover), to, promotion), false);
+                }
+                for from in squares(capture_origins(to)) {
+                    self.add_uncaptures(unmoves, &Move::new_promotion(from, to, promotion));
+                }
+            }
+        }
+    }
+
+    // Castling takes away both rights of the color, so it can only be taken back without them
+    fn add_uncastling(&self, unmoves: &mut Vec<Unmove>) {
+        let mover = !self.active_color;
+        if self.has_castling_right(mover, Castling::KingSide) || self.has_castling_right(mover, Castling::QueenSide) {
+            return;
+        }
+        let king = match mover {
+            Color::White => WHITE_KING_START,
+            Color::Black => BLACK_KING_START,
+        };
+        let occupancy = self.occupancy();
+        let kings = self.get_piece_info(PieceType::King, mover).positions;
+        let rooks = self.get_piece_info(PieceType::Rook, mover).positions;
+        // The king's target, the rook's target and the squares both came from
+        let castlings = [
+            (king >> 2, king >> 1, king | king >> 3),
+            (king << 2, king << 1, king | king << 3 | king << 4),
+        ];
+        for (king_to, rook_to, vacated) in castlings {
+            if kings & king_to != 0 && rooks & rook_to != 0 && occupancy & vacated == 0 {
+                unmoves.push(Unmove { chess_move: Move::new(PieceType::King, king, king_to), uncapture: None, en_passant: false });
+            }
+        }
+    }
+
+    // Keeps the unmoves that start from a legal position in which the move is legal
+    fn legal_unmoves(&self, unmoves: Vec<Unmove>) -> Vec<Unmove> {
+        unmoves
+            .into_iter()
+            .filter(|unmove| {
+                let previous = self.unmake_move(unmove);
+                validate_position(&previous).is_ok() && previous.is_legal(&unmove.chess_move)
+            })
+            .collect()
+    }
+}
+
+// The square behind `square` as seen by a pawn of the given color
+fn behind(square: u64, color: Color) -> u64 {
+    match color {
+        Color::White => square >> 8,
+        Color::Black => square << 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movesets::test_positions::random_positions;
+    use crate::utils::board_utils::parse_square;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::fen_writer::{board_positions, position_fields, write_fen};
+
+    fn square(name: &str) -> u64 {
+        parse_square(name).unwrap()
+    }
+
+    fn previous_fens(chess_board: &ChessBoard) -> Vec<String> {
+        let mut fens: Vec<String> = chess_board.unmoves().iter().map(|unmove| write_fen(&chess_board.unmake_move(unmove))).collect();
+        fens.sort();
+        fens
+    }
+
+    #[test]
+    fn test_unmoves_lead_back_to_the_position() {
+        let positions = random_positions(50, 4);
+        // Each playout starts again from a perft position, those pairs are not a move apart
+        for pair in positions.windows(2).filter(|pair| pair[1].move_history.len() == pair[0].move_history.len() + 1) {
+            let (before, after) = (&pair[0], &pair[1]);
+            let unmoves = after.unmoves();
+            // The move that was played is found again
+            let played = after.move_history.last().unwrap();
+            assert!(
+                unmoves.iter().any(|unmove| &unmove.chess_move == played && board_positions(&after.unmake_move(unmove)) == board_positions(before)),
+                "{} after {}",
+                write_fen(after),
+                played.to_uci()
+            );
+            // And every unmove played forwards gives this position again
+            for unmove in &unmoves {
+                let mut previous = after.unmake_move(unmove);
+                previous.make_move(&unmove.chess_move);
+                assert_eq!(position_fields(after), position_fields(&previous), "{:?}", unmove);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uncaptures() {
+        // The knight on c3 came from b1, d1, e2, a2, a4, b5, d5 or e4 with or without capturing
+        let chess_board = read_fen("4k3/8/8/8/8/2N5/8/4K3 b - - 0 1");
+        let knight_unmoves: Vec<Unmove> = chess_board.unmoves().into_iter().filter(|unmove| unmove.chess_move.piece_type == PieceType::Knight).collect();
+        assert_eq!(8 * 6, knight_unmoves.len());
+        assert!(knight_unmoves.contains(&Unmove {
+            chess_move: Move::new(PieceType::Knight, square("b1"), square("c3")),
+            uncapture: Some(PieceType::Queen),
+            en_passant: false,
+        }));
+
+        // A pawn can not be put back on the last rank
+        let back_rank = read_fen("3Nk3/8/8/8/8/8/8/4K3 b - - 0 1");
+        assert!(back_rank.unmoves().iter().all(|unmove| unmove.uncapture != Some(PieceType::Pawn)));
+    }
+
+    #[test]
+    fn test_unpromotions() {
+        let chess_board = read_fen("1Q2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+        let unpromotions: Vec<Unmove> = chess_board.unmoves().into_iter().filter(|unmove| unmove.chess_move.promotion.is_some()).collect();
+        // From b7 without a capture, from a7 and c7 with one of four pieces
+        assert_eq!(1 + 2 * 4, unpromotions.len());
+        let previous = chess_board.unmake_move(&Unmove { chess_move: Move::new_promotion(square("b7"), square("b8"), PieceType::Queen), uncapture: None, en_passant: false });
+        assert_eq!("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", write_fen(&previous));
+    }
+
+    #[test]
+    fn test_uncastling() {
+        let castlings = |chess_board: &ChessBoard| -> Vec<String> {
+            chess_board
+                .unmoves()
+                .iter()
+                .filter(|unmove| unmove.chess_move.piece_type == PieceType::King && unmove.chess_move.old_position == square("e1"))
+                .map(|unmove| write_fen(&chess_board.unmake_move(unmove)))
+                .collect()
+        };
+        assert_eq!(vec!["r4rk1/8/8/8/8/8/8/R3K2R w Q - 2 10"], castlings(&read_fen("r4rk1/8/8/8/8/8/8/2KR3R b - - 3 10")));
+        assert_eq!(vec!["r5k1/8/8/8/8/8/8/R3K2R w K - 0 10"], castlings(&read_fen("r5k1/8/8/8/8/8/8/R4RK1 b - - 0 10")));
+
+        // Not when the king would have castled out of check
+        assert!(castlings(&read_fen("r3r1k1/8/8/8/8/8/8/2KR3R b - - 3 10")).is_empty());
+    }
+
+    #[test]
+    fn test_en_passant() {
+        // Only the double push can have led to a position with an en passant square
+        let double_push = read_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1");
+        assert_eq!(vec!["4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"], previous_fens(&double_push));
+
+        // The pawn on d6 took a pawn on d5 en passant, or anything on d6 normally
+        let chess_board = read_fen("4k3/8/3P4/8/8/8/8/4K3 b - - 0 1");
+        let en_passant: Vec<Unmove> = chess_board.unmoves().into_iter().filter(|unmove| unmove.en_passant).collect();
+        assert_eq!(2, en_passant.len());
+        let previous = chess_board.unmake_move(&en_passant[0]);
+        assert_eq!(square("d6"), previous.en_passant);
+        assert!(previous.is_legal(&en_passant[0].chess_move));
+    }
+}
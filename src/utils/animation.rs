@@ -0,0 +1,156 @@
+use crate::chess::game_record::GameRecord;
+use crate::chess::piece::Color;
+use crate::chess::piece_shapes::PIECE_SIZE;
+use crate::chess::svg::{board_pieces, board_squares, coordinates, piece_definitions, square_highlights, HIGHLIGHT_COLOR};
+use crate::utils::html_report::{evaluations, MAX_EVALUATION};
+
+// Writes a game as an animated SVG that plays the moves of the main line on a loop, using SMIL
+// animation so it needs no script and plays in browsers and presentation tools.
+// Every frame is a group that is hidden until a shared clock reaches its start time.
+
+// Width of the evaluation bar and the space between the bar and the board, in board units
+const EVALUATION_BAR_WIDTH: f32 = 15.0;
+const EVALUATION_BAR_GAP: f32 = 5.0;
+// Space around the board for the coordinates
+const MARGIN: f32 = 20.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationOptions {
+    // Width of the image in pixels, the height follows from the board
+    pub size: u32,
+    // How long every position is shown, in milliseconds
+    pub delay_ms: u32,
+    // The side shown at the bottom of the board
+    pub perspective: Color,
+    pub highlight_last_move: bool,
+    // Show a bar with the evaluation from the `[%eval ...]` comments next to the board
+    pub evaluation_bar: bool,
+    pub coordinates: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        AnimationOptions {
+            size: 400,
+            delay_ms: 1000,
+            perspective: Color::White,
+            highlight_last_move: true,
+            evaluation_bar: false,
+            coordinates: false,
+        }
+    }
+}
+
+/// Writes an SVG that shows the starting position and then every move of the main line.
+pub fn write_animated_svg(record: &GameRecord, options: &AnimationOptions) -> String {
+    let board_size = 8.0 * PIECE_SIZE;
+    let margin = if options.coordinates { MARGIN } else { 0.0 };
+    let left = margin + if options.evaluation_bar { EVALUATION_BAR_WIDTH + EVALUATION_BAR_GAP } else { 0.0 };
+    let (width, height) = (left + board_size + margin, board_size + 2.0 * margin);
+    let pixel_height = (options.size as f32 * height / width).round();
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+        options.size, pixel_height, 0.0 - left, 0.0 - margin, width, height
+    );
+    svg += piece_definitions().as_str();
+    svg += board_squares(options.perspective).as_str();
+    if options.coordinates {
+        svg += coordinates(options.perspective).as_str();
+    }
+
+    // The clock restarts when it ends, so the frames start over after the last move
+    let frame_count = record.moves.len() + 1;
+    let delay = options.delay_ms as f32 / 1000.0;
+    svg += format!(
+        "<rect width=\"0\" height=\"0\"><animate id=\"clock\" attributeName=\"width\" from=\"0\" to=\"0\" dur=\"{}s\" begin=\"0s;clock.end\"/></rect>\n",
+        frame_count as f32 * delay
+    ).as_str();
+
+    let evaluations = evaluations(record);
+    let mut chess_board = record.starting_board.clone();
+    let mut evaluation = 0;
+    for ply in 0..frame_count {
+        if ply > 0 {
+            let node = &record.moves[ply - 1];
+            chess_board.make_move(&node.chess_move);
+            evaluation = evaluations[ply - 1].unwrap_or(evaluation);
+        }
+
+        svg += "<g display=\"none\">\n";
+        svg += format!("<set attributeName=\"display\" to=\"inline\" begin=\"clock.begin+{}s\" dur=\"{}s\"/>\n", ply as f32 * delay, delay).as_str();
+        if options.highlight_last_move {
+            if let Some(last_move) = chess_board.move_history.last() {
+                svg += square_highlights(&[(last_move.old_position | last_move.new_position, HIGHLIGHT_COLOR.to_string())], options.perspective).as_str();
+            }
+        }
+        svg += board_pieces(&chess_board, options.perspective).as_str();
+        if options.evaluation_bar {
+            svg += evaluation_bar(evaluation, options.perspective, left).as_str();
+        }
+        svg += "</g>\n";
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+// A bar left of the board and its coordinates, filled with White's share from White's side of the board
+fn evaluation_bar(evaluation: i32, perspective: Color, left: f32) -> String {
+    let board_size = 8.0 * PIECE_SIZE;
+    let white_share = 0.5 + evaluation.clamp(-MAX_EVALUATION, MAX_EVALUATION) as f32 / (2.0 * MAX_EVALUATION as f32);
+    // Rounded to a tenth of a unit to keep the numbers in the document short
+    let white_height = (white_share * board_size * 10.0).round() / 10.0;
+    let white_top = if perspective == Color::White { board_size - white_height } else { 0.0 };
+    let x = -left;
+    format!(
+        "<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"#404040\"/>\n<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#f0f0f0\"/>\n",
+        x, EVALUATION_BAR_WIDTH, board_size, x, white_top, EVALUATION_BAR_WIDTH, white_height
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::pgn_reader::read_pgn;
+
+    const GAME: &str = "1. e4 { [%eval 0.3] } 1... e5 { [%eval 0.2] } 2. Qh5 { [%eval -0.5] } 2... Nc6 3. Bc4 Nf6 { [%eval 10] } 4. Qxf7# 1-0";
+
+    #[test]
+    fn test_write_animated_svg() {
+        let record = read_pgn(GAME).unwrap();
+        let options = AnimationOptions { delay_ms: 500, ..AnimationOptions::default() };
+        let svg = write_animated_svg(&record, &options);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("width=\"400\" height=\"400\" viewBox=\"0 0 360 360\""));
+        // The start and seven moves, half a second each
+        assert_eq!(8, svg.matches("<g display=\"none\">").count());
+        assert!(svg.contains("dur=\"4s\" begin=\"0s;clock.end\""));
+        assert!(svg.contains("begin=\"clock.begin+0s\" dur=\"0.5s\""));
+        assert!(svg.contains("begin=\"clock.begin+3.5s\" dur=\"0.5s\""));
+        // Every frame after the first highlights the two squares of its move
+        assert_eq!(14, svg.matches("fill-opacity=\"0.6\"").count());
+        assert!(!svg.contains("fill=\"#f0f0f0\""));
+    }
+
+    #[test]
+    fn test_write_animated_svg_evaluation_bar() {
+        let record = read_pgn(GAME).unwrap();
+        let options = AnimationOptions { evaluation_bar: true, highlight_last_move: false, ..AnimationOptions::default() };
+        let svg = write_animated_svg(&record, &options);
+        assert!(svg.contains("viewBox=\"-20 0 380 360\""));
+        assert!(!svg.contains("fill-opacity=\"0.6\""));
+        // Even at the start, then White's share after each move, a move without evaluation keeps the last one
+        assert!(svg.contains("<rect x=\"-20\" y=\"180\" width=\"15\" height=\"180\" fill=\"#f0f0f0\"/>"));
+        assert!(svg.contains("<rect x=\"-20\" y=\"174.6\" width=\"15\" height=\"185.4\" fill=\"#f0f0f0\"/>"));
+        assert!(svg.contains("<rect x=\"-20\" y=\"176.4\" width=\"15\" height=\"183.6\" fill=\"#f0f0f0\"/>"));
+        assert_eq!(3, svg.matches("<rect x=\"-20\" y=\"189\" width=\"15\" height=\"171\" fill=\"#f0f0f0\"/>").count());
+        // A winning evaluation fills the whole bar
+        assert!(svg.contains("<rect x=\"-20\" y=\"0\" width=\"15\" height=\"360\" fill=\"#f0f0f0\"/>"));
+
+        // With coordinates the bar moves left of the rank numbers
+        let svg = write_animated_svg(&record, &AnimationOptions { coordinates: true, ..options });
+        assert!(svg.contains("viewBox=\"-40 -20 420 400\""));
+        assert!(svg.contains("<rect x=\"-40\" y=\"0\" width=\"15\" height=\"360\" fill=\"#404040\"/>"));
+    }
+}
@@ -1,21 +1,87 @@
 
-pub(crate) const A_FILE: u64 = 0b10000000_10000000_10000000_10000000_10000000_10000000_10000000_10000000;
-pub(crate) const B_FILE: u64 = 0b01000000_01000000_01000000_01000000_01000000_01000000_01000000_01000000;
-pub(crate) const C_FILE: u64 = 0b00100000_00100000_00100000_00100000_00100000_00100000_00100000_00100000;
-pub(crate) const D_FILE: u64 = 0b00010000_00010000_00010000_00010000_00010000_00010000_00010000_00010000;
-pub(crate) const E_FILE: u64 = 0b00001000_00001000_00001000_00001000_00001000_00001000_00001000_00001000;
-pub(crate) const F_FILE: u64 = 0b00000100_00000100_00000100_00000100_00000100_00000100_00000100_00000100;
-pub(crate) const G_FILE: u64 = 0b00000010_00000010_00000010_00000010_00000010_00000010_00000010_00000010;
-pub(crate) const H_FILE: u64 = 0b00000001_00000001_00000001_00000001_00000001_00000001_00000001_00000001;
-
-pub(crate) const RANK_EIGHT: u64 =  0b11111111_00000000_00000000_00000000_00000000_00000000_00000000_00000000;
-pub(crate) const RANK_SEVEN: u64 =  0b00000000_11111111_00000000_00000000_00000000_00000000_00000000_00000000;
-pub(crate) const RANK_SIX: u64 =    0b00000000_00000000_11111111_00000000_00000000_00000000_00000000_00000000;
-pub(crate) const RANK_FIVE: u64 =   0b00000000_00000000_00000000_11111111_00000000_00000000_00000000_00000000;
-pub(crate) const RANK_FOUR: u64 =   0b00000000_00000000_00000000_00000000_11111111_00000000_00000000_00000000;
-pub(crate) const RANK_THREE: u64 =  0b00000000_00000000_00000000_00000000_00000000_11111111_00000000_00000000;
-pub(crate) const RANK_TWO: u64 =    0b00000000_00000000_00000000_00000000_00000000_00000000_11111111_00000000;
-pub(crate) const RANK_ONE: u64 =    0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_11111111;
-
-pub(crate) const RANKS: [u64;8] = [RANK_ONE, RANK_TWO, RANK_THREE, RANK_FOUR, RANK_FIVE, RANK_SIX, RANK_SEVEN, RANK_EIGHT];
-pub(crate) const FILES: [u64;8] = [H_FILE, G_FILE,F_FILE,E_FILE,D_FILE,C_FILE,B_FILE,A_FILE];
\ No newline at end of file
+pub const A_FILE: u64 = 0b10000000_10000000_10000000_10000000_10000000_10000000_10000000_10000000;
+pub const B_FILE: u64 = 0b01000000_01000000_01000000_01000000_01000000_01000000_01000000_01000000;
+pub const C_FILE: u64 = 0b00100000_00100000_00100000_00100000_00100000_00100000_00100000_00100000;
+pub const D_FILE: u64 = 0b00010000_00010000_00010000_00010000_00010000_00010000_00010000_00010000;
+pub const E_FILE: u64 = 0b00001000_00001000_00001000_00001000_00001000_00001000_00001000_00001000;
+pub const F_FILE: u64 = 0b00000100_00000100_00000100_00000100_00000100_00000100_00000100_00000100;
+pub const G_FILE: u64 = 0b00000010_00000010_00000010_00000010_00000010_00000010_00000010_00000010;
+pub const H_FILE: u64 = 0b00000001_00000001_00000001_00000001_00000001_00000001_00000001_00000001;
+
+pub const RANK_EIGHT: u64 =  0b11111111_00000000_00000000_00000000_00000000_00000000_00000000_00000000;
+pub const RANK_SEVEN: u64 =  0b00000000_11111111_00000000_00000000_00000000_00000000_00000000_00000000;
+pub const RANK_SIX: u64 =    0b00000000_00000000_11111111_00000000_00000000_00000000_00000000_00000000;
+pub const RANK_FIVE: u64 =   0b00000000_00000000_00000000_11111111_00000000_00000000_00000000_00000000;
+pub const RANK_FOUR: u64 =   0b00000000_00000000_00000000_00000000_11111111_00000000_00000000_00000000;
+pub const RANK_THREE: u64 =  0b00000000_00000000_00000000_00000000_00000000_11111111_00000000_00000000;
+pub const RANK_TWO: u64 =    0b00000000_00000000_00000000_00000000_00000000_00000000_11111111_00000000;
+pub const RANK_ONE: u64 =    0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_11111111;
+
+pub const RANKS: [u64;8] = [RANK_ONE, RANK_TWO, RANK_THREE, RANK_FOUR, RANK_FIVE, RANK_SIX, RANK_SEVEN, RANK_EIGHT];
+pub const FILES: [u64;8] = [H_FILE, G_FILE,F_FILE,E_FILE,D_FILE,C_FILE,B_FILE,A_FILE];
+
+/// Returns the single-bit bitboard for the square on file `x` (0 = A) and rank `y` (0 = rank 1).
+pub fn square_from_xy(x: usize, y: usize) -> u64 {
+    1 << (y * 8 + (7 - x))
+}
+
+/// Returns the file (0 = A) and rank (0 = rank 1) of a single-bit bitboard.
+pub fn square_to_xy(square: u64) -> (usize, usize) {
+    let index = square.trailing_zeros() as usize;
+    (7 - index % 8, index / 8)
+}
+
+/// Returns the algebraic name of a single-bit bitboard, e.g. `e4`.
+pub fn square_name(square: u64) -> String {
+    let (x, y) = square_to_xy(square);
+    format!("{}{}", (b'a' + x as u8) as char, y + 1)
+}
+
+/// Parses an algebraic square name such as `e4` into a single-bit bitboard.
+pub fn parse_square(name: &str) -> Option<u64> {
+    let mut characters = name.chars();
+    let file = characters.next()?;
+    let rank = characters.next()?;
+    if characters.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+    Some(square_from_xy(file as usize - 'a' as usize, rank as usize - '1' as usize))
+}
+
+/// Iterates over the single-bit bitboards of all squares set in `bitboard`, from the lowest bit up.
+pub fn squares(mut bitboard: u64) -> impl Iterator<Item = u64> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let square = bitboard & bitboard.wrapping_neg();
+        bitboard ^= square;
+        Some(square)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_square_name(){
+        assert_eq!("a1", square_name(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_10000000));
+        assert_eq!("h8", square_name(0b00000001_00000000_00000000_00000000_00000000_00000000_00000000_00000000));
+        assert_eq!("e4", square_name(0b00000000_00000000_00000000_00000000_00001000_00000000_00000000_00000000));
+    }
+
+    #[test]
+    fn test_parse_square(){
+        assert_eq!(Some(0b00000000_00000000_00000000_00000000_00001000_00000000_00000000_00000000), parse_square("e4"));
+        assert_eq!(None, parse_square("i1"));
+        assert_eq!(None, parse_square("a9"));
+        assert_eq!(None, parse_square("a10"));
+    }
+
+    #[test]
+    fn test_squares(){
+        let result: Vec<u64> = squares(RANK_ONE & (A_FILE | H_FILE)).collect();
+        assert_eq!(vec![H_FILE & RANK_ONE, A_FILE & RANK_ONE], result);
+    }
+}
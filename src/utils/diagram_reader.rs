@@ -0,0 +1,185 @@
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::{ChessBoard, BLACK_KING_SIDE_ROOK, BLACK_KING_START, BLACK_QUEEN_SIDE_ROOK, WHITE_KING_SIDE_ROOK, WHITE_KING_START, WHITE_QUEEN_SIDE_ROOK};
+use crate::chess::piece::{Color, PieceType, SinglePieceInfo};
+use crate::chess::visualization::{DARK_SQUARE, LIGHT_SQUARE, PIECE_CHARACTERS};
+
+// Functions that read board diagrams back into boards, the inverse of `Visualize::visualize`.
+// Diagrams are read from rank 8 at the top down to rank 1, for example:
+//
+// 8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜          r n b q k b n r
+// 7 ♟︎ ♟︎ ♟︎ ♟︎ ♟︎ ♟︎ ♟︎ ♟︎          p p p p p p p p
+// 6 □ ■ □ ■ □ ■ □ ■          . . . . . . . .
+// ...                   or   ...
+// 1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖          R N B Q K B N R
+//   A B C D E F G H
+
+// Unicode variation selector printed after the black pawn, it is not a square of its own
+const VARIATION_SELECTOR: char = '\u{FE0E}';
+
+/// Reads a Unicode or ASCII diagram into a board with White to move. Rank labels, file labels and
+/// spaces are ignored. Castling rights are given for every king and rook still on their starting square.
+pub fn read_diagram(diagram: &str) -> ChessBoard {
+    let rows: Vec<Vec<Option<(PieceType, Color)>>> = diagram
+        .lines()
+        .map(read_row)
+        .filter(|row| !row.is_empty())
+        .collect();
+    if rows.len() != 8 {
+        panic!("Invalid diagram, expected 8 ranks but found {}", rows.len());
+    }
+
+    let mut chess_board = ChessBoard::new(true);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, square) in row.iter().enumerate() {
+            if let Some((piece_type, color)) = *square {
+                chess_board.set_square(SinglePieceInfo { piece_type, color, position_x: x, position_y: 7 - y });
+            }
+        }
+    }
+
+    chess_board.clear_castling();
+    let pieces = |piece_type: PieceType, color: Color| chess_board.get_piece_info(piece_type, color).positions;
+    let castling = [
+        (Color::White, Castling::KingSide, WHITE_KING_START, WHITE_KING_SIDE_ROOK),
+        (Color::White, Castling::QueenSide, WHITE_KING_START, WHITE_QUEEN_SIDE_ROOK),
+        (Color::Black, Castling::KingSide, BLACK_KING_START, BLACK_KING_SIDE_ROOK),
+        (Color::Black, Castling::QueenSide, BLACK_KING_START, BLACK_QUEEN_SIDE_ROOK),
+    ];
+    let available: Vec<(Color, Castling)> = castling
+        .into_iter()
+        .filter(|&(color, _, king, rook)| pieces(PieceType::King, color) & king != 0 && pieces(PieceType::Rook, color) & rook != 0)
+        .map(|(color, castling, _, _)| (color, castling))
+        .collect();
+    for (color, castling) in available {
+        chess_board.set_castling(color, castling);
+    }
+    chess_board
+}
+
+// Reads the squares of one line, returns an empty row for lines that are not a rank (e.g. file labels)
+fn read_row(line: &str) -> Vec<Option<(PieceType, Color)>> {
+    let characters: Vec<char> = line
+        .chars()
+        .filter(|&character| !character.is_whitespace() && character != VARIATION_SELECTOR)
+        .collect();
+
+    // Skip the rank label in front of the squares
+    let squares = match characters.len() {
+        9 if characters[0].is_ascii_digit() => &characters[1..],
+        8 => &characters[..],
+        _ => return Vec::new(),
+    };
+
+    let mut row = Vec::new();
+    for &character in squares {
+        match read_square(character) {
+            Some(square) => row.push(square),
+            None => return Vec::new(),
+        }
+    }
+    row
+}
+
+// Returns Some(None) for an empty square and None for a character that is not a square at all
+fn read_square(character: char) -> Option<Option<(PieceType, Color)>> {
+    if character == '.' || DARK_SQUARE.starts_with(character) || LIGHT_SQUARE.starts_with(character) {
+        return Some(None);
+    }
+    for (piece, glyph) in PIECE_CHARACTERS {
+        if glyph.starts_with(character) {
+            return Some(Some(piece));
+        }
+    }
+    let piece_type = PieceType::from_letter(character)?;
+    let color = if character.is_ascii_uppercase() { Color::White } else { Color::Black };
+    Some(Some((piece_type, color)))
+}
+
+/// Reads an 8x8 picture of a bitboard, rank 8 at the top. Marked squares are written as `x`, `X` or
+/// `1`, empty squares as `.` or `0`. Spaces are ignored, so a rank can be written as `. x . . x . . .`.
+/// Rank and file labels as printed by `visualize_bitboard` are skipped.
+pub fn read_bitboard(picture: &str) -> u64 {
+    let rows: Vec<String> = picture
+        .lines()
+        .map(|line| line.chars().filter(|character| !character.is_whitespace()).collect::<String>())
+        .filter(|line| !line.is_empty() && line != "ABCDEFGH")
+        .map(|line| if line.chars().count() == 9 { line.chars().skip(1).collect() } else { line })
+        .collect();
+    if rows.len() != 8 || rows.iter().any(|row| row.chars().count() != 8) {
+        panic!("Invalid bitboard picture, expected 8 ranks of 8 squares:\n{}", picture);
+    }
+
+    let mut bitboard = 0;
+    for row in rows {
+        for character in row.chars() {
+            bitboard <<= 1;
+            match character {
+                'x' | 'X' | '1' => bitboard |= 1,
+                '.' | '0' => {}
+                _ => panic!("Invalid character {} in bitboard picture", character),
+            }
+        }
+    }
+    bitboard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::visualization::Visualize;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::fen_writer::write_fen;
+
+    #[test]
+    fn test_read_diagram_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ];
+        for fen in fens {
+            let diagram = read_fen(fen).visualize();
+            assert_eq!(fen, write_fen(&read_diagram(&diagram)));
+        }
+    }
+
+    #[test]
+    fn test_read_diagram_ascii() {
+        let chess_board = read_diagram("
+            r . . . k . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . P . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . K . . R
+        ");
+        assert_eq!("r3k3/8/8/8/4P3/8/8/4K2R w Kq - 0 1", write_fen(&chess_board));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_read_diagram_missing_rank() {
+        read_diagram("
+            8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜
+            1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖
+              A B C D E F G H
+        ");
+    }
+
+    #[test]
+    fn test_read_bitboard() {
+        let bitboard = read_bitboard("
+            x . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . x
+        ");
+        assert_eq!(0b10000000_00000000_00000000_00000000_00000000_00000000_00000000_00000001, bitboard);
+    }
+}
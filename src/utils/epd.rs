@@ -0,0 +1,325 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::Move;
+use crate::utils::fen_reader::try_read_fen;
+use crate::utils::fen_writer::position_fields;
+use crate::utils::san::{move_to_san, parse_san};
+
+// Functions for reading and writing Extended Position Description (EPD) records.
+// A record holds the first four FEN fields followed by operations, for example:
+// r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - bm Bb5; id "Ruy Lopez";
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum EpdOperand {
+    // A move written in SAN, resolved against the position of the record
+    Move(Move),
+    Integer(i64),
+    // A string that was written between double quotes
+    String(String),
+    // Any other unquoted token
+    Word(String),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct EpdOperation {
+    pub opcode: String,
+    pub operands: Vec<EpdOperand>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct EpdRecord {
+    pub chess_board: ChessBoard,
+    pub operations: Vec<EpdOperation>,
+}
+
+impl EpdRecord {
+    pub fn new(chess_board: ChessBoard) -> EpdRecord {
+        EpdRecord { chess_board, operations: Vec::new() }
+    }
+
+    /// Returns the operands of the first operation with the given opcode.
+    pub fn operation(&self, opcode: &str) -> Option<&[EpdOperand]> {
+        self.operations
+            .iter()
+            .find(|operation| operation.opcode == opcode)
+            .map(|operation| operation.operands.as_slice())
+    }
+
+    /// Sets an operation, replacing the operands if the opcode already exists.
+    pub fn set_operation(&mut self, opcode: &str, operands: Vec<EpdOperand>) {
+        match self.operations.iter_mut().find(|operation| operation.opcode == opcode) {
+            Some(operation) => operation.operands = operands,
+            None => self.operations.push(EpdOperation { opcode: opcode.to_string(), operands }),
+        }
+    }
+
+    /// The best moves of the `bm` operation.
+    pub fn best_moves(&self) -> Vec<Move> {
+        self.moves_of("bm")
+    }
+
+    /// The moves to avoid of the `am` operation.
+    pub fn avoid_moves(&self) -> Vec<Move> {
+        self.moves_of("am")
+    }
+
+    /// The predicted variation of the `pv` operation.
+    pub fn principal_variation(&self) -> Vec<Move> {
+        self.moves_of("pv")
+    }
+
+    /// The identifier of the `id` operation.
+    pub fn id(&self) -> Option<&str> {
+        self.string_of("id")
+    }
+
+    /// The comment of one of the `c0` to `c9` operations.
+    pub fn comment(&self, index: u8) -> Option<&str> {
+        self.string_of(&format!("c{}", index))
+    }
+
+    /// The centipawn evaluation of the `ce` operation, from the side to move.
+    pub fn centipawn_evaluation(&self) -> Option<i64> {
+        self.integer_of("ce")
+    }
+
+    /// The analysis depth of the `acd` operation.
+    pub fn analysis_depth(&self) -> Option<i64> {
+        self.integer_of("acd")
+    }
+
+    fn moves_of(&self, opcode: &str) -> Vec<Move> {
+        self.operation(opcode)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|operand| match operand {
+                EpdOperand::Move(chess_move) => Some(chess_move.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn string_of(&self, opcode: &str) -> Option<&str> {
+        match self.operation(opcode)?.first()? {
+            EpdOperand::String(value) | EpdOperand::Word(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn integer_of(&self, opcode: &str) -> Option<i64> {
+        match self.operation(opcode)?.first()? {
+            EpdOperand::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a single EPD line. The `hmvc` and `fmvn` operations also set the move clocks of the board.
+pub fn read_epd(epd: &str) -> Result<EpdRecord, String> {
+    let epd = epd.trim();
+    let mut fields = Vec::new();
+    let mut rest = epd;
+    for _ in 0..4 {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if end == 0 {
+            return Err(format!("EPD record needs four position fields: {}", epd));
+        }
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+
+    let mut record = EpdRecord::new(try_read_fen(&fields.join(" "))?);
+    for operation in split_operations(rest)? {
+        let mut tokens = operation.into_iter();
+        let Some(opcode) = tokens.next() else {
+            continue;
+        };
+        let operands = read_operands(&record.chess_board, &opcode, tokens.collect())?;
+        record.operations.push(EpdOperation { opcode, operands });
+    }
+
+    if let Some(halfmove_clock) = record.integer_of("hmvc") {
+        record.chess_board.halfmove_clock =
+            u32::try_from(halfmove_clock).map_err(|_| format!("Invalid halfmove clock in EPD record: {}", halfmove_clock))?;
+    }
+    if let Some(fullmove_number) = record.integer_of("fmvn") {
+        record.chess_board.fullmove_number =
+            u32::try_from(fullmove_number).map_err(|_| format!("Invalid fullmove number in EPD record: {}", fullmove_number))?;
+    }
+    Ok(record)
+}
+
+/// Reads all EPD records in a text, skipping empty lines.
+pub fn read_epd_lines(text: &str) -> Result<Vec<EpdRecord>, String> {
+    text.lines().filter(|line| !line.trim().is_empty()).map(read_epd).collect()
+}
+
+/// Writes an EPD record on a single line, with moves written in SAN.
+pub fn write_epd(record: &EpdRecord) -> String {
+    let chess_board = &record.chess_board;
+    let mut result = position_fields(chess_board);
+
+    for operation in &record.operations {
+        result.push(' ');
+        result += &operation.opcode;
+        // The moves of a predicted variation are played one after the other
+        let mut position = chess_board.clone();
+        for operand in &operation.operands {
+            result.push(' ');
+            match operand {
+                EpdOperand::Move(chess_move) => {
+                    result += &move_to_san(&position, chess_move);
+                    if operation.opcode == "pv" {
+                        position.make_move(chess_move);
+                    }
+                }
+                EpdOperand::Integer(value) => result += &value.to_string(),
+                // A quote inside the string would end it early, so quotes are dropped
+                EpdOperand::String(value) => result += &format!("\"{}\"", value.replace('"', "")),
+                EpdOperand::Word(value) => result += value,
+            }
+        }
+        result.push(';');
+    }
+    result
+}
+
+// Splits the operations on semicolons and each operation on whitespace, keeping quoted strings whole.
+// Quoted strings keep their quotes so they can be told apart from other tokens.
+fn split_operations(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut operations = Vec::new();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut in_string = false;
+
+    for character in text.chars() {
+        match character {
+            '"' => {
+                token.push(character);
+                if in_string {
+                    tokens.push(std::mem::take(&mut token));
+                }
+                in_string = !in_string;
+            }
+            _ if in_string => token.push(character),
+            ';' => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+                operations.push(std::mem::take(&mut tokens));
+            }
+            _ if character.is_whitespace() => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            _ => token.push(character),
+        }
+    }
+
+    if in_string {
+        return Err(format!("Unterminated string in EPD operations: {}", text));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    if !tokens.is_empty() {
+        return Err(format!("EPD operation is not terminated by a semicolon: {}", tokens.join(" ")));
+    }
+    Ok(operations)
+}
+
+fn read_operands(chess_board: &ChessBoard, opcode: &str, tokens: Vec<String>) -> Result<Vec<EpdOperand>, String> {
+    let mut position = chess_board.clone();
+    let mut operands = Vec::new();
+    for token in tokens {
+        let operand = if let Some(value) = token.strip_prefix('"').and_then(|token| token.strip_suffix('"')) {
+            EpdOperand::String(value.to_string())
+        } else if matches!(opcode, "bm" | "am" | "pv" | "sm") {
+            let chess_move = parse_san(&position, &token)
+                .ok_or_else(|| format!("Move {} of operation {} is not legal in the position", token, opcode))?;
+            if opcode == "pv" {
+                position.make_move(&chess_move);
+            }
+            EpdOperand::Move(chess_move)
+        } else if let Ok(value) = token.parse::<i64>() {
+            EpdOperand::Integer(value)
+        } else {
+            EpdOperand::Word(token)
+        };
+        operands.push(operand);
+    }
+    Ok(operands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::piece::Color;
+
+    const RUY_LOPEZ: &str = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - bm Bb5; id \"Ruy; Lopez\"; c0 \"main line\";";
+
+    #[test]
+    fn test_read_epd() {
+        let record = read_epd(RUY_LOPEZ).unwrap();
+        assert_eq!(Color::White, record.chess_board.active_color);
+        assert_eq!(vec!["f1b5"], record.best_moves().iter().map(Move::to_uci).collect::<Vec<_>>());
+        assert_eq!(Some("Ruy; Lopez"), record.id());
+        assert_eq!(Some("main line"), record.comment(0));
+        assert_eq!(None, record.comment(1));
+    }
+
+    #[test]
+    fn test_read_epd_analysis_operations() {
+        let epd = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - acd 12; ce 25; pv e4 e5 Nf3; hmvc 0; fmvn 1; am a4 h4;";
+        let record = read_epd(epd).unwrap();
+        assert_eq!(Some(12), record.analysis_depth());
+        assert_eq!(Some(25), record.centipawn_evaluation());
+        assert_eq!(vec!["e2e4", "e7e5", "g1f3"], record.principal_variation().iter().map(Move::to_uci).collect::<Vec<_>>());
+        assert_eq!(2, record.avoid_moves().len());
+    }
+
+    #[test]
+    fn test_read_epd_sets_move_clocks() {
+        let record = read_epd("4k3/8/8/8/8/8/8/4K3 b - - hmvc 7; fmvn 40;").unwrap();
+        assert_eq!(7, record.chess_board.halfmove_clock);
+        assert_eq!(40, record.chess_board.fullmove_number);
+    }
+
+    #[test]
+    fn test_read_epd_errors() {
+        assert!(read_epd("4k3/8/8/8/8/8/8/4K3 w -").is_err());
+        assert!(read_epd("4k3/8/8/8/8/8/4K3 w - - id \"short\";").is_err());
+        assert!(read_epd("4k3/8/8/8/8/8/8/4K3 w - - bm Kd1").is_err());
+        assert!(read_epd("4k3/8/8/8/8/8/8/4K3 w - - bm Ke9;").is_err());
+        assert!(read_epd("4k3/8/8/8/8/8/8/4K3 w - - id \"open;").is_err());
+        assert!(read_epd("4k3/8/8/8/8/8/8/4K3 w - - hmvc -1;").is_err());
+        assert!(read_epd("4k3/8/8/8/8/8/8/4K3 w - - fmvn 4294967296;").is_err());
+    }
+
+    #[test]
+    fn test_write_epd_round_trip() {
+        let epds = [
+            RUY_LOPEZ,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - acd 12; ce -25; pv e4 e5 Nf3;",
+            "4k3/8/8/8/8/8/8/R3K2R w K - bm O-O; hmvc 3;",
+        ];
+        for epd in epds {
+            assert_eq!(epd, write_epd(&read_epd(epd).unwrap()));
+        }
+
+        let mut record = read_epd("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+        record.set_operation("c0", vec![EpdOperand::String("say \"hi\"".to_string())]);
+        let epd = write_epd(&record);
+        assert_eq!("4k3/8/8/8/8/8/8/4K3 w - - c0 \"say hi\";", epd);
+        assert_eq!(Some(&[EpdOperand::String("say hi".to_string())][..]), read_epd(&epd).unwrap().operation("c0"));
+    }
+
+    #[test]
+    fn test_read_epd_lines() {
+        let text = format!("{}\n\n4k3/8/8/8/8/8/8/4K3 b - - id \"kings\";\n", RUY_LOPEZ);
+        let records = read_epd_lines(&text).unwrap();
+        assert_eq!(2, records.len());
+        assert_eq!(Some("kings"), records[1].id());
+    }
+}
@@ -1,26 +1,57 @@
-use crate::chess::{ChessBoard, SinglePieceInfo, PieceType, Color, Castling};
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::piece::{SinglePieceInfo, PieceType, Color};
+use crate::chess::castling::Castling;
+use crate::utils::board_utils::parse_square;
 
 // A function that reads a FEN string and returns a ChessBoard object
 // Example of such a string: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
-pub(crate) fn read_fen(fen: &str) -> ChessBoard{
+// Panics if the FEN string is invalid, use `try_read_fen` for FEN strings from outside sources.
+pub fn read_fen(fen: &str) -> ChessBoard{
+    try_read_fen(fen).unwrap_or_else(|error| panic!("{}", error))
+}
+
+// Same as `read_fen`, but returns an error message instead of panicking on an invalid FEN string
+pub fn try_read_fen(fen: &str) -> Result<ChessBoard, String>{
     // parse the fen notation into a vector of strings
     let fen_vec: Vec<&str> = fen.split_whitespace().collect();
+    if fen_vec.len() < 4 || fen_vec.len() > 6 {
+        return Err(format!("Invalid number of fields in FEN string: {}", fen));
+    }
     // create a new ChessBoard object
     let mut chess_board = ChessBoard::new(true);
     // set the board positions
-    set_board_positions(&mut chess_board, fen_vec[0]);
+    set_board_positions(&mut chess_board, fen_vec[0])?;
     // set the active color
-    set_active_color(&mut chess_board, fen_vec[1]);
+    set_active_color(&mut chess_board, fen_vec[1])?;
     // set the castling rights
-    set_castling_rights(&mut chess_board, fen_vec[2]);
-    chess_board
+    set_castling_rights(&mut chess_board, fen_vec[2])?;
+    // set the en passant square, the move clocks are optional and default to the start of a game
+    set_en_passant(&mut chess_board, fen_vec[3])?;
+    chess_board.halfmove_clock = match fen_vec.get(4) {
+        Some(clock) => clock.parse().map_err(|_| format!("Invalid halfmove clock in FEN string: {}", clock))?,
+        None => 0,
+    };
+    chess_board.fullmove_number = match fen_vec.get(5) {
+        Some(number) => number.parse().map_err(|_| format!("Invalid fullmove number in FEN string: {}", number))?,
+        None => 1,
+    };
+    Ok(chess_board)
+
+}
 
+fn set_en_passant(chess_board: &mut ChessBoard, en_passant: &str) -> Result<(), String> {
+    if en_passant != "-" {
+        chess_board.en_passant = parse_square(en_passant).ok_or(format!("Invalid en passant square in FEN string: {}", en_passant))?;
+    }
+    Ok(())
 }
 
-fn set_castling_rights(chess_board: &mut ChessBoard, castling_string: &str) {
+fn set_castling_rights(chess_board: &mut ChessBoard, castling_string: &str) -> Result<(), String> {
+    // only the rights listed in the string are available
+    chess_board.clear_castling();
     // if the castling rights are "-", do nothing
     if castling_string == "-" {
-        return;
+        return Ok(());
     }
     // loop through the castling rights string
     for character in castling_string.chars() {
@@ -36,31 +67,36 @@ fn set_castling_rights(chess_board: &mut ChessBoard, castling_string: &str) {
                 chess_board.set_castling(Color::Black, Castling::KingSide);
             },
             'q' => {
-                chess_board.set_castling(Color::Black, Castling::KingSide);
+                chess_board.set_castling(Color::Black, Castling::QueenSide);
             },
-            _ => panic!("Invalid character in FEN string"),
+            _ => return Err(format!("Invalid castling character in FEN string: {}", character)),
         }
     }
+    Ok(())
 }
 
-fn set_active_color(chess_board: &mut ChessBoard, active_color: &str) {
+fn set_active_color(chess_board: &mut ChessBoard, active_color: &str) -> Result<(), String> {
     chess_board.set_active_color(match active_color {
         "w" => Color::White,
         "b" => Color::Black,
-        _ => panic!("Invalid color in FEN string"),
+        _ => return Err(format!("Invalid color in FEN string: {}", active_color)),
     });
+    Ok(())
 }
 
-fn set_board_positions(chessboard: &mut ChessBoard, positions: &str){
+fn set_board_positions(chessboard: &mut ChessBoard, positions: &str) -> Result<(), String>{
     // split the positions string into a vector of strings
-    let positions_vec: Vec<&str> = positions.split("/").collect();
+    let positions_vec: Vec<&str> = positions.split('/').collect();
+    if positions_vec.len() != 8 {
+        return Err(format!("Invalid number of ranks in FEN string: {}", positions));
+    }
     // loop through the vector of strings
     for (y, row) in positions_vec.iter().enumerate() {
         // loop through each character in the string
         let mut x = 0;
-        for (_, character) in row.chars().enumerate() {
+        for character in row.chars() {
             // if the character is a digit, skip that many squares
-            if character.is_digit(10) {
+            if character.is_ascii_digit() {
                 // convert the character to a number
                 let number = character.to_digit(10).unwrap();
                 // skip that many squares
@@ -71,7 +107,7 @@ fn set_board_positions(chessboard: &mut ChessBoard, positions: &str){
                 // convert the character to a piece
                 let mut piece_type: PieceType = PieceType::Pawn;
                 let mut color: Color = Color::Black;
-                let piece = match character {
+                match character {
                     'p' => { },
                     'P' => {
                         color = Color::White;
@@ -111,9 +147,14 @@ fn set_board_positions(chessboard: &mut ChessBoard, positions: &str){
                         piece_type = PieceType::King;
                         color = Color::White;
                     },
-                    _ => panic!("Invalid character in FEN string"),
+                    _ => return Err(format!("Invalid piece character in FEN string: {}", character)),
                 };
 
+                // a rank can not hold more than 8 squares
+                if x >= 8 {
+                    return Err(format!("Too many squares in FEN rank: {}", row));
+                }
+
                 // set the square to the piece
                 chessboard.set_square(SinglePieceInfo {
                     piece_type,
@@ -125,7 +166,12 @@ fn set_board_positions(chessboard: &mut ChessBoard, positions: &str){
                 x += 1;
             }
         }
+        // every rank must describe exactly 8 squares
+        if x != 8 {
+            return Err(format!("Invalid number of squares in FEN rank: {}", row));
+        }
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -151,10 +197,43 @@ mod tests {
         assert_eq!(chess_board.black_queens().positions, start_board.black_queens().positions);
         assert_eq!(chess_board.black_kings().positions, start_board.black_kings().positions);
         assert_eq!(Color::Black, chess_board.active_color);
-        assert_eq!(true, chess_board.white_king_side_castle);
-        assert_eq!(true, chess_board.white_queen_side_castle);
-        assert_eq!(true, chess_board.black_king_side_castle);
-        assert_eq!(true, chess_board.black_queen_side_castle);
+        assert!(chess_board.white_king_side_castle);
+        assert!(chess_board.white_queen_side_castle);
+        assert!(chess_board.black_king_side_castle);
+        assert!(chess_board.black_queen_side_castle);
+        assert_eq!(0b00000000_00000000_00001000_00000000_00000000, chess_board.en_passant);
+        assert_eq!(0, chess_board.halfmove_clock);
+        assert_eq!(1, chess_board.fullmove_number);
 
     }
+
+    #[test]
+    fn test_try_read_fen_errors() {
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1").is_err());
+        assert!(try_read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w").is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_read_fen_invalid() {
+        read_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1");
+    }
+
+    #[test]
+    fn test_read_fen_castling_rights() {
+        let chess_board = read_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 3 20");
+        assert!(chess_board.white_king_side_castle);
+        assert!(!chess_board.white_queen_side_castle);
+        assert!(!chess_board.black_king_side_castle);
+        assert!(chess_board.black_queen_side_castle);
+        assert_eq!(3, chess_board.halfmove_clock);
+        assert_eq!(20, chess_board.fullmove_number);
+    }
 }
\ No newline at end of file
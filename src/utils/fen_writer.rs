@@ -0,0 +1,89 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::piece::Color;
+use crate::utils::board_utils::{square_from_xy, square_name};
+
+// A function that writes a ChessBoard object as a FEN string, the inverse of `read_fen`
+// Example of such a string: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+pub fn write_fen(chess_board: &ChessBoard) -> String {
+    format!("{} {} {}", position_fields(chess_board), chess_board.halfmove_clock, chess_board.fullmove_number)
+}
+
+// Writes the first four FEN fields, which are shared with EPD: pieces, active color, castling and en passant
+pub(crate) fn position_fields(chess_board: &ChessBoard) -> String {
+    let active_color = if chess_board.active_color == Color::White { "w" } else { "b" };
+    let en_passant = if chess_board.en_passant == 0 { "-".to_string() } else { square_name(chess_board.en_passant) };
+    format!("{} {} {} {}", board_positions(chess_board), active_color, castling_rights(chess_board), en_passant)
+}
+
+// Writes only the piece placement field, from rank 8 down to rank 1
+pub(crate) fn board_positions(chess_board: &ChessBoard) -> String {
+    let mut result = String::new();
+    for y in (0..8).rev() {
+        let mut empty = 0;
+        for x in 0..8 {
+            match chess_board.piece_at(square_from_xy(x, y)) {
+                Some((piece_type, color)) => {
+                    if empty > 0 {
+                        result += &empty.to_string();
+                        empty = 0;
+                    }
+                    let letter = piece_type.letter();
+                    result.push(if color == Color::White { letter } else { letter.to_ascii_lowercase() });
+                }
+                None => empty += 1,
+            }
+        }
+        if empty > 0 {
+            result += &empty.to_string();
+        }
+        if y > 0 {
+            result.push('/');
+        }
+    }
+    result
+}
+
+pub(crate) fn castling_rights(chess_board: &ChessBoard) -> String {
+    let mut result = String::new();
+    if chess_board.white_king_side_castle {
+        result.push('K');
+    }
+    if chess_board.white_queen_side_castle {
+        result.push('Q');
+    }
+    if chess_board.black_king_side_castle {
+        result.push('k');
+    }
+    if chess_board.black_queen_side_castle {
+        result.push('q');
+    }
+    if result.is_empty() {
+        result.push('-');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fen_reader::read_fen;
+
+    #[test]
+    fn test_write_fen_start_position() {
+        let chess_board = ChessBoard::new(false);
+        assert_eq!("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", write_fen(&chess_board));
+    }
+
+    #[test]
+    fn test_write_fen_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 3 20",
+        ];
+        for fen in fens {
+            assert_eq!(fen, write_fen(&read_fen(fen)));
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::game_record::{GameRecord, MoveNode};
+use crate::chess::piece::Color;
+use crate::chess::svg::{render_svg, SvgOptions, HIGHLIGHT_COLOR};
+use crate::utils::san::move_to_san;
+
+// Writes a game as a single HTML page for players to review: a board that steps through the game,
+// a clickable move list with annotations, an evaluation chart and markers for bad moves.
+// Evaluations are read from `[%eval 0.35]` or `[%eval #-3]` commands in the move comments.
+
+// Evaluations are clamped to this many centipawns, also used for mates
+pub(crate) const MAX_EVALUATION: i32 = 1000;
+const CHART_WIDTH: f32 = 600.0;
+const CHART_HEIGHT: f32 = 120.0;
+
+/// How bad a move is, from its NAG or from the drop in evaluation for the side that played it.
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+pub enum MoveClassification {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl MoveClassification {
+    // Smallest drop in centipawns for each classification
+    const THRESHOLDS: [(MoveClassification, i32); 3] = [
+        (MoveClassification::Blunder, 300),
+        (MoveClassification::Mistake, 100),
+        (MoveClassification::Inaccuracy, 50),
+    ];
+
+    fn from_nag(nag: u8) -> Option<MoveClassification> {
+        match nag {
+            4 => Some(MoveClassification::Blunder),
+            2 => Some(MoveClassification::Mistake),
+            6 => Some(MoveClassification::Inaccuracy),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            MoveClassification::Inaccuracy => "inaccuracy",
+            MoveClassification::Mistake => "mistake",
+            MoveClassification::Blunder => "blunder",
+        }
+    }
+}
+
+/// The evaluation in centipawns from White's side after each move of the main line, if its comment
+/// has an `[%eval ...]` command. Mates count as `MAX_EVALUATION`.
+pub fn evaluations(record: &GameRecord) -> Vec<Option<i32>> {
+    record
+        .moves
+        .iter()
+        .map(|node| node.comment.as_deref().and_then(comment_evaluation))
+        .collect()
+}
+
+/// Classifies each move of the main line. A `?`, `??` or `?!` NAG is used when present,
+/// otherwise the loss of evaluation compared to the previous move. The first move is compared with
+/// the evaluation in the game comment, or with an equal position if there is none.
+pub fn classify_moves(record: &GameRecord) -> Vec<Option<MoveClassification>> {
+    let evaluations = evaluations(record);
+    let starting_evaluation = record.comment.as_deref().and_then(comment_evaluation).unwrap_or(0);
+    let mut chess_board = record.starting_board.clone();
+    let mut classifications = Vec::new();
+
+    for (index, node) in record.moves.iter().enumerate() {
+        let from_nag = node.nags.iter().find_map(|&nag| MoveClassification::from_nag(nag));
+        let before = match index {
+            0 => Some(starting_evaluation),
+            _ => evaluations[index - 1],
+        };
+        let from_evaluation = match (before, evaluations[index]) {
+            (Some(before), Some(after)) => {
+                let drop = if chess_board.active_color == Color::White { before - after } else { after - before };
+                MoveClassification::THRESHOLDS
+                    .iter()
+                    .find(|(_, threshold)| drop >= *threshold)
+                    .map(|(classification, _)| *classification)
+            }
+            _ => None,
+        };
+        classifications.push(from_nag.or(from_evaluation));
+        chess_board.make_move(&node.chess_move);
+    }
+    classifications
+}
+
+/// Writes the report as a self-contained HTML page, without any external scripts or images.
+pub fn write_html_report(record: &GameRecord) -> String {
+    let white = record.tag("White").unwrap_or("?");
+    let black = record.tag("Black").unwrap_or("?");
+    let title = format!("{} - {}", escape_html(white), escape_html(black));
+    let details: Vec<String> = ["Event", "Site", "Date"]
+        .iter()
+        .filter_map(|name| record.tag(name))
+        .filter(|value| !value.starts_with('?'))
+        .map(escape_html)
+        .collect();
+
+    let mut html = String::new();
+    html += "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n";
+    html += format!("<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n", title, STYLE).as_str();
+    html += format!("<h1>{} <span class=\"result\">{}</span></h1>\n", title, record.result.token()).as_str();
+    html += format!("<p class=\"details\">{}</p>\n", details.join(", ")).as_str();
+    if let Some(comment) = &record.comment {
+        html += format!("<p class=\"comment\">{}</p>\n", escape_html(&display_comment(comment))).as_str();
+    }
+
+    html += "<div class=\"report\">\n<div class=\"board\">\n";
+    html += positions(record).as_str();
+    html += "<div class=\"controls\"><button onclick=\"show(0)\">&#x23EE;</button><button onclick=\"show(ply - 1)\">&#x25C0;</button>\
+        <button onclick=\"show(ply + 1)\">&#x25B6;</button><button onclick=\"show(last)\">&#x23ED;</button></div>\n";
+    html += "</div>\n<div class=\"moves\">\n";
+    html += move_list(record).as_str();
+    html += "</div>\n</div>\n";
+    html += evaluation_chart(&evaluations(record)).as_str();
+    html += format!("<script>\nconst last = {};\n{}</script>\n</body>\n</html>\n", record.moves.len(), SCRIPT).as_str();
+    html
+}
+
+// A rendered board for the start and for every move of the main line, only the current one is shown
+fn positions(record: &GameRecord) -> String {
+    let mut chess_board = record.starting_board.clone();
+    let mut html = String::new();
+    for ply in 0..=record.moves.len() {
+        if ply > 0 {
+            chess_board.make_move(&record.moves[ply - 1].chess_move);
+        }
+        let mut options = SvgOptions { size: 400, ..SvgOptions::default() };
+        if let Some(last_move) = chess_board.move_history.last() {
+            options.highlights.push((last_move.old_position | last_move.new_position, HIGHLIGHT_COLOR.to_string()));
+        }
+        let hidden = if ply == 0 { "" } else { " hidden" };
+        html += format!("<div class=\"position\" id=\"ply-{}\"{}>\n{}</div>\n", ply, hidden, render_svg(&chess_board, &options)).as_str();
+    }
+    html
+}
+
+fn move_list(record: &GameRecord) -> String {
+    let classifications = classify_moves(record);
+    let mut chess_board = record.starting_board.clone();
+    let mut html = String::new();
+
+    for (index, node) in record.moves.iter().enumerate() {
+        let ply = index + 1;
+        let pre_comment = shown_comment(&node.pre_comment);
+        if let Some(comment) = &pre_comment {
+            html += format!("<span class=\"comment\">{}</span>\n", escape_html(comment)).as_str();
+        }
+        if chess_board.active_color == Color::White {
+            html += format!("<span class=\"number\">{}.</span>", chess_board.fullmove_number).as_str();
+        } else if index == 0 || pre_comment.is_some() {
+            html += format!("<span class=\"number\">{}...</span>", chess_board.fullmove_number).as_str();
+        }
+
+        let mut classes = vec!["move"];
+        if let Some(classification) = classifications[index] {
+            classes.push(classification.name());
+        }
+        let symbols: String = node.nags.iter().filter_map(|&nag| nag_symbol(nag)).collect();
+        html += format!(
+            "<span class=\"{}\" id=\"move-{}\" onclick=\"show({})\">{}{}</span>\n",
+            classes.join(" "), ply, ply, escape_html(&move_to_san(&chess_board, &node.chess_move)), symbols
+        ).as_str();
+
+        if let Some(comment) = shown_comment(&node.comment) {
+            html += format!("<span class=\"comment\">{}</span>\n", escape_html(&comment)).as_str();
+        }
+        for variation in &node.variations {
+            html += format!("<span class=\"variation\">({})</span>\n", escape_html(&variation_text(&chess_board, variation))).as_str();
+        }
+        chess_board.make_move(&node.chess_move);
+    }
+    html += format!("<span class=\"result\">{}</span>\n", record.result.token()).as_str();
+    html
+}
+
+// Variations are shown as plain text, the board only follows the main line
+fn variation_text(chess_board: &ChessBoard, nodes: &[MoveNode]) -> String {
+    let mut chess_board = chess_board.clone();
+    let mut words = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let pre_comment = shown_comment(&node.pre_comment);
+        if let Some(comment) = &pre_comment {
+            words.push(comment.clone());
+        }
+        if chess_board.active_color == Color::White {
+            words.push(format!("{}.", chess_board.fullmove_number));
+        } else if index == 0 || pre_comment.is_some() {
+            words.push(format!("{}...", chess_board.fullmove_number));
+        }
+        words.push(move_to_san(&chess_board, &node.chess_move));
+        chess_board.make_move(&node.chess_move);
+    }
+    words.join(" ")
+}
+
+// A line through the evaluations with White's advantage upwards, clicking a point shows that move
+fn evaluation_chart(evaluations: &[Option<i32>]) -> String {
+    if evaluations.iter().all(Option::is_none) {
+        return String::new();
+    }
+    let step = CHART_WIDTH / evaluations.len().max(1) as f32;
+    let y = |evaluation: i32| CHART_HEIGHT / 2.0 - evaluation as f32 / MAX_EVALUATION as f32 * CHART_HEIGHT / 2.0;
+
+    let mut chart = format!(
+        "<svg class=\"chart\" xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n\
+        <line x1=\"0\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#999999\"/>\n",
+        CHART_WIDTH, CHART_HEIGHT, CHART_HEIGHT / 2.0, CHART_WIDTH, CHART_HEIGHT / 2.0
+    );
+    let points: Vec<(usize, f32, f32)> = evaluations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, evaluation)| evaluation.map(|evaluation| (index + 1, (index as f32 + 0.5) * step, y(evaluation))))
+        .collect();
+    let line: Vec<String> = points.iter().map(|(_, x, y)| format!("{},{}", x, y)).collect();
+    chart += format!("<polyline points=\"{}\" fill=\"none\" stroke=\"#333333\" stroke-width=\"2\"/>\n", line.join(" ")).as_str();
+    for (ply, x, y) in points {
+        chart += format!("<circle cx=\"{}\" cy=\"{}\" r=\"3\" onclick=\"show({})\"/>\n", x, y, ply).as_str();
+    }
+    chart += "</svg>\n";
+    chart
+}
+
+// Reads the evaluation of a `[%eval ...]` command, in pawns or as a mate in a number of moves
+fn comment_evaluation(comment: &str) -> Option<i32> {
+    let start = comment.find("[%eval ")? + "[%eval ".len();
+    let value = comment[start..].split([']', ',', ' ']).next()?;
+    if let Some(mate) = value.strip_prefix('#') {
+        let moves: i32 = mate.parse().ok()?;
+        return Some(if moves < 0 { -MAX_EVALUATION } else { MAX_EVALUATION });
+    }
+    let pawns: f32 = value.parse().ok()?;
+    Some(((pawns * 100.0).round() as i32).clamp(-MAX_EVALUATION, MAX_EVALUATION))
+}
+
+// Leaves out the embedded commands such as `[%eval 0.35]` and `[%clk 0:03:00]`
+// The text of a comment as shown in the report, if anything is left without the commands
+fn shown_comment(comment: &Option<String>) -> Option<String> {
+    comment.as_deref().map(display_comment).filter(|comment| !comment.is_empty())
+}
+
+fn display_comment(comment: &str) -> String {
+    let mut result = String::new();
+    let mut rest = comment;
+    while let Some(start) = rest.find("[%") {
+        result += &rest[..start];
+        rest = rest[start..].find(']').map_or("", |end| &rest[start + end + 1..]);
+    }
+    result += rest;
+    result.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+pub(crate) fn nag_symbol(nag: u8) -> Option<&'static str> {
+    match nag {
+        1 => Some("!"),
+        2 => Some("?"),
+        3 => Some("!!"),
+        4 => Some("??"),
+        5 => Some("!?"),
+        6 => Some("?!"),
+        _ => None,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222222; }
+.details { color: #666666; }
+.report { display: flex; gap: 2em; align-items: flex-start; }
+.controls { text-align: center; }
+.controls button { font-size: 1.2em; margin: 0.2em; }
+.moves { max-width: 30em; line-height: 1.8; }
+.number { color: #666666; margin-right: 0.3em; }
+.move { cursor: pointer; padding: 0.1em 0.3em; border-radius: 0.2em; }
+.move.current { background: #cdd26a; }
+.inaccuracy { color: #56b4e9; }
+.mistake { color: #e69f00; font-weight: bold; }
+.blunder { color: #d55e00; font-weight: bold; }
+.comment { color: #2a6f2a; margin: 0 0.3em; }
+.variation { color: #777777; margin: 0 0.3em; }
+.chart { margin-top: 1em; background: #f5f5f5; }
+.chart circle { cursor: pointer; fill: #333333; }
+";
+
+const SCRIPT: &str = "\
+let ply = 0;
+function show(next) {
+  next = Math.max(0, Math.min(last, next));
+  document.getElementById('ply-' + ply).hidden = true;
+  document.getElementById('ply-' + next).hidden = false;
+  const previous = document.getElementById('move-' + ply);
+  if (previous) previous.classList.remove('current');
+  const current = document.getElementById('move-' + next);
+  if (current) current.classList.add('current');
+  ply = next;
+}
+document.addEventListener('keydown', event => {
+  if (event.key === 'ArrowLeft') show(ply - 1);
+  if (event.key === 'ArrowRight') show(ply + 1);
+  if (event.key === 'Home') show(0);
+  if (event.key === 'End') show(last);
+});
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::pgn_reader::read_pgn;
+
+    const ANALYSED_GAME: &str = "[White \"Alice <3\"]\n[Black \"Bob\"]\n[Result \"0-1\"]\n\n\
+        1. f3 { [%eval -0.4] } 1... e5 { [%eval -0.3] Fine. } 2. g4 { [%eval -10.5] [%clk 0:01:00] } \
+        (2. e4 { [%eval 0.2] }) 2... Qh4# $1 { [%eval #-1] } 0-1";
+
+    #[test]
+    fn test_evaluations() {
+        let record = read_pgn(ANALYSED_GAME).unwrap();
+        assert_eq!(vec![Some(-40), Some(-30), Some(-MAX_EVALUATION), Some(-MAX_EVALUATION)], evaluations(&record));
+    }
+
+    #[test]
+    fn test_classify_moves() {
+        let record = read_pgn(ANALYSED_GAME).unwrap();
+        assert_eq!(vec![None, None, Some(MoveClassification::Blunder), None], classify_moves(&record));
+
+        let annotated = read_pgn("1. e4 e5?! 2. Qh5 Nc6 3. Bc4 Nf6?? 4. Qxf7# 1-0").unwrap();
+        let classifications = classify_moves(&annotated);
+        assert_eq!(Some(MoveClassification::Inaccuracy), classifications[1]);
+        assert_eq!(Some(MoveClassification::Blunder), classifications[5]);
+        assert_eq!(None, classifications[6]);
+
+        // The first move is compared with the evaluation of the game comment, or with 0
+        let first_move = read_pgn("{ [%eval 0.2] } 1. f3 { [%eval -3.0] } *").unwrap();
+        assert_eq!(vec![Some(MoveClassification::Blunder)], classify_moves(&first_move));
+        let without_start = read_pgn("1. f3 { [%eval -1.2] } *").unwrap();
+        assert_eq!(vec![Some(MoveClassification::Mistake)], classify_moves(&without_start));
+    }
+
+    #[test]
+    fn test_write_html_report() {
+        let record = read_pgn(ANALYSED_GAME).unwrap();
+        let html = write_html_report(&record);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Alice &lt;3 - Bob</title>"));
+        // One board for the start and one after every move
+        assert_eq!(5, html.matches("class=\"position\"").count());
+        assert!(html.contains("<span class=\"move blunder\" id=\"move-3\" onclick=\"show(3)\">g4</span>"));
+        assert!(html.contains("<span class=\"move\" id=\"move-4\" onclick=\"show(4)\">Qh4#!</span>"));
+        assert!(html.contains("<span class=\"comment\">Fine.</span>"));
+        assert!(html.contains("<span class=\"variation\">(2. e4)</span>"));
+        let pre_comment = write_html_report(&read_pgn("1. e4 e5 ({Also good} 1... c5) *").unwrap());
+        assert!(pre_comment.contains("<span class=\"variation\">(Also good 1... c5)</span>"));
+        assert!(!html.contains("[%"));
+        assert_eq!(4, html.matches("r=\"3\" onclick").count());
+        assert!(html.contains("const last = 4;"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!("&lt;a title=&#39;x&#39;&gt;&amp;&quot;", escape_html("<a title='x'>&\""));
+    }
+
+    #[test]
+    fn test_display_comment() {
+        assert_eq!("Fine.", display_comment("[%eval 0.3] Fine. [%clk 0:01:00]"));
+        assert_eq!("", display_comment("[%eval 0.3]"));
+        assert_eq!(Some(35), comment_evaluation("[%eval 0.35]"));
+        assert_eq!(Some(MAX_EVALUATION), comment_evaluation("[%eval #3]"));
+        assert_eq!(None, comment_evaluation("no evaluation"));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::game_record::{GameRecord, MoveNode};
+use crate::chess::piece::Color;
+use crate::chess::piece_shapes::{piece_shapes, Shape, PIECE_SIZE};
+use crate::chess::svg::{piece_colors, square_origin, DARK_SQUARE_COLOR, LIGHT_SQUARE_COLOR};
+use crate::utils::board_utils::{square_from_xy, squares};
+use crate::utils::fen_writer::write_fen;
+use crate::utils::html_report::nag_symbol;
+use crate::utils::san::move_to_san;
+
+// Functions for writing boards and games as LaTeX, either for the skak package (also understood by
+// xskak) or as TikZ pictures that draw the pieces themselves and only need the tikz package.
+
+/// How diagrams are written in LaTeX.
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+pub enum LatexDiagramStyle {
+    // \fenboard and \showboard from the skak package, moves in \mainline
+    Skak,
+    // A tikzpicture per diagram, moves as plain text
+    Tikz,
+}
+
+/// Writes a diagram for the skak package: `\fenboard{...}` followed by `\showboard`.
+pub fn write_skak_diagram(chess_board: &ChessBoard) -> String {
+    format!("\\fenboard{{{}}}\n\\showboard\n", write_fen(chess_board))
+}
+
+/// Writes a tikzpicture of the board with coordinates, one square is half a centimeter.
+pub fn write_tikz_diagram(chess_board: &ChessBoard, perspective: Color) -> String {
+    let mut tikz = "\\begin{tikzpicture}[x=0.5cm,y=0.5cm]\n".to_string();
+    tikz += format!("\\definecolor{{lightsquare}}{{HTML}}{{{}}}\n", &LIGHT_SQUARE_COLOR[1..]).as_str();
+    tikz += format!("\\definecolor{{darksquare}}{{HTML}}{{{}}}\n", &DARK_SQUARE_COLOR[1..]).as_str();
+
+    for y in 0..8 {
+        for x in 0..8 {
+            let color = if (x + y) % 2 == 0 { "darksquare" } else { "lightsquare" };
+            let (left, bottom) = to_tikz(square_origin(square_from_xy(x, y), perspective), (0.0, PIECE_SIZE));
+            tikz += format!("\\fill[{}] ({},{}) rectangle +(1,1);\n", color, left, bottom).as_str();
+        }
+    }
+
+    for index in 0..8 {
+        let (left, top) = square_origin(square_from_xy(index, index), perspective);
+        let (column, row) = (left / PIECE_SIZE, 7.0 - top / PIECE_SIZE);
+        tikz += format!("\\node at ({},-0.4) {{\\tiny {}}};\n", number(column + 0.5), (b'a' + index as u8) as char).as_str();
+        tikz += format!("\\node at (-0.4,{}) {{\\tiny {}}};\n", number(row + 0.5), index + 1).as_str();
+    }
+
+    for square in squares(chess_board.occupancy()) {
+        let (piece_type, color) = chess_board.piece_at(square).unwrap();
+        let (fill, stroke) = piece_colors(color);
+        let style = format!("fill={},draw={},line width=0.3pt,line join=round", color_name(fill), color_name(stroke));
+        let origin = square_origin(square, perspective);
+        for shape in piece_shapes(piece_type) {
+            match shape {
+                Shape::Polygon(vertices) => {
+                    let path: Vec<String> = vertices
+                        .iter()
+                        .map(|&vertex| {
+                            let (x, y) = to_tikz(origin, vertex);
+                            format!("({},{})", x, y)
+                        })
+                        .collect();
+                    tikz += format!("\\filldraw[{}] {} -- cycle;\n", style, path.join(" -- ")).as_str();
+                }
+                Shape::Circle(center, radius) => {
+                    let (x, y) = to_tikz(origin, *center);
+                    tikz += format!("\\filldraw[{}] ({},{}) circle ({});\n", style, x, y, number(radius / PIECE_SIZE)).as_str();
+                }
+            }
+        }
+    }
+
+    tikz += "\\end{tikzpicture}\n";
+    tikz
+}
+
+/// Writes the main line of a game as LaTeX with a diagram after each of the given plies, where
+/// ply 0 is the starting position. Comments, NAGs and variations are written along with the moves.
+pub fn write_latex_game(record: &GameRecord, diagram_plies: &[usize], style: LatexDiagramStyle) -> String {
+    let mut latex = String::new();
+    if style == LatexDiagramStyle::Skak {
+        latex += if write_fen(&record.starting_board) == write_fen(&ChessBoard::new(false)) {
+            "\\newgame\n".to_string()
+        } else {
+            format!("\\fenboard{{{}}}\n", write_fen(&record.starting_board))
+        }.as_str();
+    }
+    if let Some(comment) = &record.comment {
+        latex += format!("{}\n\n", escape_latex(comment)).as_str();
+    }
+
+    let mut chess_board = record.starting_board.clone();
+    // The moves since the last diagram or comment, written together
+    let mut moves: Vec<String> = Vec::new();
+    for ply in 0..=record.moves.len() {
+        if ply > 0 {
+            let node = &record.moves[ply - 1];
+            let before = chess_board.clone();
+            moves.push(move_text(&before, node, moves.is_empty()));
+            chess_board.make_move(&node.chess_move);
+
+            // Comments and variations interrupt the moves, which then need a new move number
+            if node.comment.is_some() || !node.variations.is_empty() {
+                latex += flush_moves(&mut moves, style).as_str();
+                if let Some(comment) = &node.comment {
+                    latex += format!("{}\n", escape_latex(comment)).as_str();
+                }
+                for variation in &node.variations {
+                    latex += variation_text(&before, variation, style).as_str();
+                }
+            }
+        }
+
+        if diagram_plies.contains(&ply) {
+            latex += flush_moves(&mut moves, style).as_str();
+            latex += "\n";
+            latex += match style {
+                LatexDiagramStyle::Skak => "\\showboard\n".to_string(),
+                LatexDiagramStyle::Tikz => format!("\\begin{{center}}\n{}\\end{{center}}\n", write_tikz_diagram(&chess_board, Color::White)),
+            }.as_str();
+            latex += "\n";
+        }
+    }
+    latex += flush_moves(&mut moves, style).as_str();
+    latex += format!("{}\n", escape_latex(record.result.token())).as_str();
+    latex
+}
+
+fn flush_moves(moves: &mut Vec<String>, style: LatexDiagramStyle) -> String {
+    if moves.is_empty() {
+        return String::new();
+    }
+    let text = moves.join(" ");
+    moves.clear();
+    match style {
+        LatexDiagramStyle::Skak => format!("\\mainline{{{}}}\n", text),
+        LatexDiagramStyle::Tikz => format!("{}\n", escape_latex(&text)),
+    }
+}
+
+// The move in SAN with its move number and annotation symbols, e.g. `12... Nf6!?`
+fn move_text(chess_board: &ChessBoard, node: &MoveNode, needs_number: bool) -> String {
+    let mut text = String::new();
+    if chess_board.active_color == Color::White {
+        text += format!("{}. ", chess_board.fullmove_number).as_str();
+    } else if needs_number {
+        text += format!("{}... ", chess_board.fullmove_number).as_str();
+    }
+    text += move_to_san(chess_board, &node.chess_move).as_str();
+    for &nag in &node.nags {
+        text += nag_symbol(nag).unwrap_or("");
+    }
+    text
+}
+
+// Variations are shown without changing the board, skak has \variation for that
+fn variation_text(chess_board: &ChessBoard, nodes: &[MoveNode], style: LatexDiagramStyle) -> String {
+    let mut chess_board = chess_board.clone();
+    let mut words = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        words.push(move_text(&chess_board, node, index == 0));
+        chess_board.make_move(&node.chess_move);
+    }
+    match style {
+        LatexDiagramStyle::Skak => format!("(\\variation{{{}}})\n", words.join(" ")),
+        LatexDiagramStyle::Tikz => format!("({})\n", escape_latex(&words.join(" "))),
+    }
+}
+
+// Converts a point inside a square, in SVG units with y down, to TikZ coordinates of one per square
+fn to_tikz((left, top): (f32, f32), (x, y): (f32, f32)) -> (String, String) {
+    (number((left + x) / PIECE_SIZE), number(8.0 - (top + y) / PIECE_SIZE))
+}
+
+// Numbers with at most three decimals, to keep the pictures short
+fn number(value: f32) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    // Avoid writing -0
+    format!("{}", rounded + 0.0)
+}
+
+fn color_name(color: &str) -> &'static str {
+    if color == "#ffffff" { "white" } else { "black" }
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut result = String::new();
+    for character in text.chars() {
+        match character {
+            '\\' => result += "\\textbackslash{}",
+            '~' => result += "\\textasciitilde{}",
+            '^' => result += "\\textasciicircum{}",
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                result.push('\\');
+                result.push(character);
+            }
+            _ => result.push(character),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::pgn_reader::read_pgn;
+
+    #[test]
+    fn test_write_skak_diagram() {
+        let expected = "\\fenboard{rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1}\n\\showboard\n";
+        assert_eq!(expected, write_skak_diagram(&ChessBoard::new(false)));
+    }
+
+    #[test]
+    fn test_write_tikz_diagram() {
+        let tikz = write_tikz_diagram(&ChessBoard::new(false), Color::White);
+        assert!(tikz.starts_with("\\begin{tikzpicture}"));
+        assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+        assert!(tikz.contains("\\definecolor{lightsquare}{HTML}{f0d9b5}"));
+        assert_eq!(64, tikz.matches(" rectangle +(1,1);").count());
+        // a1 is dark and in the bottom left corner, h1 is light
+        assert!(tikz.contains("\\fill[darksquare] (0,0) rectangle +(1,1);"));
+        assert!(tikz.contains("\\fill[lightsquare] (7,0) rectangle +(1,1);"));
+        assert!(tikz.contains("\\node at (0.5,-0.4) {\\tiny a};"));
+        // The crenellations of the white rook on a1 start at the top left of the rook
+        assert!(tikz.contains("\\filldraw[fill=white,draw=black,line width=0.3pt,line join=round] (0.244,0.8) -- "));
+
+        let flipped = write_tikz_diagram(&ChessBoard::new(false), Color::Black);
+        assert!(flipped.contains("\\node at (7.5,-0.4) {\\tiny a};"));
+        assert!(flipped.contains("\\filldraw[fill=white,draw=black,line width=0.3pt,line join=round] (7.244,7.8) -- "));
+    }
+
+    #[test]
+    fn test_write_latex_game_skak() {
+        let record = read_pgn("1. e4 e5 2. Nf3 {Developing.} 2... Nc6 (2... d6 3. d4) 3. Bb5 a6?! 1/2-1/2").unwrap();
+        let expected = "\\newgame\n\n\\showboard\n\n\\mainline{1. e4 e5 2. Nf3}\nDeveloping.\n\\mainline{2... Nc6}\n\
+            (\\variation{2... d6 3. d4})\n\\mainline{3. Bb5}\n\n\\showboard\n\n\\mainline{3... a6?!}\n1/2-1/2\n";
+        assert_eq!(expected, write_latex_game(&record, &[0, 5], LatexDiagramStyle::Skak));
+    }
+
+    #[test]
+    fn test_write_latex_game_tikz() {
+        let record = read_pgn("[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n\n12... Kd7 13. e4 {100% winning?} *").unwrap();
+        let latex = write_latex_game(&record, &[2], LatexDiagramStyle::Tikz);
+        assert!(latex.starts_with("12... Kd7 13. e4\n100\\% winning?\n\n\\begin{center}\n\\begin{tikzpicture}"));
+        assert!(latex.ends_with("\\end{tikzpicture}\n\\end{center}\n\n*\n"));
+        assert!(!latex.contains("\\fenboard"));
+    }
+
+    #[test]
+    fn test_escape_latex() {
+        assert_eq!("\\#1 \\& \\{x\\} \\textbackslash{}", escape_latex("#1 & {x} \\"));
+    }
+}
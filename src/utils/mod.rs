@@ -1 +1,16 @@
-pub(crate) mod board_utils;
\ No newline at end of file
+pub mod board_utils;
+pub mod fen_reader;
+pub mod fen_writer;
+pub mod san;
+pub mod pgn_writer;
+pub mod pgn_reader;
+pub mod html_report;
+pub mod animation;
+pub mod latex;
+pub mod epd;
+pub mod diagram_reader;
+pub mod packed_position;
+pub mod position_validator;
+pub mod random_positions;
+#[cfg(feature = "serde")]
+pub mod serialization;
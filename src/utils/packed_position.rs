@@ -0,0 +1,224 @@
+use std::io::{self, Read, Write};
+
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::piece::{Color, PieceType, SinglePieceInfo};
+use crate::utils::board_utils::{square_to_xy, squares};
+
+// A fixed-size binary encoding of a position for storing large datasets. The layout is:
+//
+// bytes  0..8   occupancy bitboard, little endian
+// bytes  8..24  one nibble per occupied square from the lowest bit up, the low nibble first:
+//               0-5 white pawn, knight, bishop, rook, queen, king and 6-11 the black pieces
+// byte  24      bit 0 black to move, bits 1-4 castling rights in the order K, Q, k, q
+// byte  25      index of the en passant square, 0 if there is none
+// bytes 26..28  halfmove clock, little endian
+// bytes 28..30  fullmove number, little endian
+//
+// The move history of the board is not stored.
+
+pub const PACKED_POSITION_SIZE: usize = 30;
+const MAX_PIECES: u32 = 32;
+
+pub type PackedPosition = [u8; PACKED_POSITION_SIZE];
+
+/// Encodes a board. Fails for boards with more than 32 pieces or move clocks that do not fit in 16 bits.
+pub fn encode_position(chess_board: &ChessBoard) -> Result<PackedPosition, String> {
+    let occupancy = chess_board.occupancy();
+    if occupancy.count_ones() > MAX_PIECES {
+        return Err(format!("Can not encode a position with {} pieces", occupancy.count_ones()));
+    }
+    let halfmove_clock = u16::try_from(chess_board.halfmove_clock)
+        .map_err(|_| format!("Halfmove clock {} is too large to encode", chess_board.halfmove_clock))?;
+    let fullmove_number = u16::try_from(chess_board.fullmove_number)
+        .map_err(|_| format!("Fullmove number {} is too large to encode", chess_board.fullmove_number))?;
+
+    let mut packed = [0u8; PACKED_POSITION_SIZE];
+    packed[0..8].copy_from_slice(&occupancy.to_le_bytes());
+    for (index, square) in squares(occupancy).enumerate() {
+        let (piece_type, color) = chess_board.piece_at(square).unwrap();
+        packed[8 + index / 2] |= piece_code(piece_type, color) << (4 * (index % 2));
+    }
+
+    let mut flags = u8::from(chess_board.active_color == Color::Black);
+    let castling = [
+        (Color::White, Castling::KingSide),
+        (Color::White, Castling::QueenSide),
+        (Color::Black, Castling::KingSide),
+        (Color::Black, Castling::QueenSide),
+    ];
+    for (index, (color, side)) in castling.into_iter().enumerate() {
+        if chess_board.has_castling_right(color, side) {
+            flags |= 1 << (index + 1);
+        }
+    }
+    packed[24] = flags;
+    packed[25] = if chess_board.en_passant == 0 { 0 } else { chess_board.en_passant.trailing_zeros() as u8 };
+    packed[26..28].copy_from_slice(&halfmove_clock.to_le_bytes());
+    packed[28..30].copy_from_slice(&fullmove_number.to_le_bytes());
+    Ok(packed)
+}
+
+/// Decodes a board written by `encode_position`.
+pub fn decode_position(packed: &PackedPosition) -> Result<ChessBoard, String> {
+    let occupancy = u64::from_le_bytes(packed[0..8].try_into().unwrap());
+    if occupancy.count_ones() > MAX_PIECES {
+        return Err(format!("Packed position has {} pieces", occupancy.count_ones()));
+    }
+
+    let mut chess_board = ChessBoard::new(true);
+    for (index, square) in squares(occupancy).enumerate() {
+        let code = (packed[8 + index / 2] >> (4 * (index % 2))) & 0x0F;
+        let (piece_type, color) = piece_from_code(code).ok_or_else(|| format!("Invalid piece code {}", code))?;
+        let (position_x, position_y) = square_to_xy(square);
+        chess_board.set_square(SinglePieceInfo { piece_type, color, position_x, position_y });
+    }
+
+    let flags = packed[24];
+    if flags >> 5 != 0 {
+        return Err(format!("Invalid flags {:#010b} in packed position", flags));
+    }
+    chess_board.active_color = if flags & 1 == 0 { Color::White } else { Color::Black };
+    chess_board.white_king_side_castle = flags & 0b10 != 0;
+    chess_board.white_queen_side_castle = flags & 0b100 != 0;
+    chess_board.black_king_side_castle = flags & 0b1000 != 0;
+    chess_board.black_queen_side_castle = flags & 0b10000 != 0;
+
+    let en_passant = packed[25];
+    if en_passant >= 64 {
+        return Err(format!("Invalid en passant square index {}", en_passant));
+    }
+    chess_board.en_passant = if en_passant == 0 { 0 } else { 1 << en_passant };
+    chess_board.halfmove_clock = u16::from_le_bytes([packed[26], packed[27]]) as u32;
+    chess_board.fullmove_number = u16::from_le_bytes([packed[28], packed[29]]) as u32;
+    Ok(chess_board)
+}
+
+fn piece_code(piece_type: PieceType, color: Color) -> u8 {
+    let index = PieceType::ALL.iter().position(|&other| other == piece_type).unwrap() as u8;
+    if color == Color::White { index } else { index + 6 }
+}
+
+fn piece_from_code(code: u8) -> Option<(PieceType, Color)> {
+    match code {
+        0..=5 => Some((PieceType::ALL[code as usize], Color::White)),
+        6..=11 => Some((PieceType::ALL[code as usize - 6], Color::Black)),
+        _ => None,
+    }
+}
+
+/// Writes packed positions one after the other to a stream, e.g. a buffered file.
+pub struct PositionWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> PositionWriter<W> {
+    pub fn new(writer: W) -> PositionWriter<W> {
+        PositionWriter { writer }
+    }
+
+    pub fn write(&mut self, chess_board: &ChessBoard) -> io::Result<()> {
+        let packed = encode_position(chess_board).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        self.writer.write_all(&packed)
+    }
+
+    /// Flushes and returns the underlying stream.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads packed positions from a stream until it ends.
+pub struct PositionReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> PositionReader<R> {
+    pub fn new(reader: R) -> PositionReader<R> {
+        PositionReader { reader }
+    }
+}
+
+impl<R: Read> Iterator for PositionReader<R> {
+    type Item = io::Result<ChessBoard>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut packed = [0u8; PACKED_POSITION_SIZE];
+        let mut filled = 0;
+        while filled < PACKED_POSITION_SIZE {
+            match self.reader.read(&mut packed[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated packed position"))),
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        Some(decode_position(&packed).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::fen_writer::write_fen;
+
+    const FENS: [&str; 4] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 17 301",
+        "8/8/8/8/8/8/8/8 w - - 0 1",
+    ];
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        for fen in FENS {
+            let packed = encode_position(&read_fen(fen)).unwrap();
+            assert_eq!(fen, write_fen(&decode_position(&packed).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_encode_start_position() {
+        let packed = encode_position(&ChessBoard::new(false)).unwrap();
+        assert_eq!(0xFFFF00000000FFFF, u64::from_le_bytes(packed[0..8].try_into().unwrap()));
+        // h1 is the lowest bit and holds a white rook (3), g1 a white knight (1)
+        assert_eq!(0x13, packed[8]);
+        assert_eq!(0b11110, packed[24]);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let mut chess_board = ChessBoard::new(false);
+        chess_board.halfmove_clock = 70000;
+        assert!(encode_position(&chess_board).is_err());
+
+        let mut packed = encode_position(&ChessBoard::new(false)).unwrap();
+        packed[8] = 0xFF;
+        assert!(decode_position(&packed).is_err());
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        let mut writer = PositionWriter::new(Vec::new());
+        for fen in FENS {
+            writer.write(&read_fen(fen)).unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+        assert_eq!(FENS.len() * PACKED_POSITION_SIZE, bytes.len());
+
+        let fens: Vec<String> = PositionReader::new(bytes.as_slice())
+            .map(|chess_board| write_fen(&chess_board.unwrap()))
+            .collect();
+        assert_eq!(FENS.to_vec(), fens);
+    }
+
+    #[test]
+    fn test_stream_truncated() {
+        let bytes = encode_position(&ChessBoard::new(false)).unwrap();
+        let mut reader = PositionReader::new(&bytes[..PACKED_POSITION_SIZE - 1]);
+        assert!(reader.next().unwrap().is_err());
+    }
+}
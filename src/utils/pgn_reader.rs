@@ -0,0 +1,320 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::game_record::{GameRecord, GameResult, MoveNode};
+use crate::utils::fen_reader::try_read_fen;
+use crate::utils::fen_writer::write_fen;
+use crate::utils::san::parse_san;
+
+// Functions for reading games in Portable Game Notation (PGN), the inverse of `write_pgn`.
+// Reads the tag pairs, SAN movetext, comments, NAGs (also written as `!`, `?!`, ...) and variations.
+
+// Move suffixes and the NAG they stand for
+const SUFFIX_ANNOTATIONS: [(&str, u8); 6] = [("!!", 3), ("??", 4), ("!?", 5), ("?!", 6), ("!", 1), ("?", 2)];
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    OpenVariation,
+    CloseVariation,
+    // Move numbers, moves and results
+    Symbol(String),
+}
+
+/// Reads the first game of a PGN text.
+pub fn read_pgn(pgn: &str) -> Result<GameRecord, String> {
+    read_pgn_games(pgn)?.into_iter().next().ok_or("No game found in PGN".to_string())
+}
+
+/// Reads all games of a PGN text, e.g. a whole database file.
+pub fn read_pgn_games(pgn: &str) -> Result<Vec<GameRecord>, String> {
+    let tokens = tokenize(pgn)?;
+    let mut games = Vec::new();
+    let mut position = 0;
+    while position < tokens.len() {
+        games.push(read_game(&tokens, &mut position)?);
+    }
+    Ok(games)
+}
+
+fn read_game(tokens: &[Token], position: &mut usize) -> Result<GameRecord, String> {
+    let mut tags = Vec::new();
+    while let Some(Token::Tag(name, value)) = tokens.get(*position) {
+        tags.push((name.clone(), value.clone()));
+        *position += 1;
+    }
+
+    let starting_board = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => try_read_fen(fen)?,
+        None => ChessBoard::new(false),
+    };
+    let mut record = GameRecord::new(starting_board.clone());
+    record.result = tags
+        .iter()
+        .find(|(name, _)| name == "Result")
+        .map_or(GameResult::Unknown, |(_, token)| parse_result(token).unwrap_or(GameResult::Unknown));
+    record.tags = tags;
+
+    // A comment before the first move belongs to the game
+    while let Some(Token::Comment(comment)) = tokens.get(*position) {
+        append_comment(&mut record.comment, comment);
+        *position += 1;
+    }
+    record.moves = read_line(&starting_board, tokens, position)?;
+
+    match tokens.get(*position) {
+        Some(Token::Symbol(symbol)) => {
+            record.result = parse_result(symbol).ok_or(format!("Unexpected token in PGN: {}", symbol))?;
+            *position += 1;
+        }
+        Some(Token::CloseVariation) => return Err("Unexpected ')' in PGN".to_string()),
+        _ => {}
+    }
+    Ok(record)
+}
+
+// Reads moves until the end of the variation, the game result or the tags of the next game
+fn read_line(chess_board: &ChessBoard, tokens: &[Token], position: &mut usize) -> Result<Vec<MoveNode>, String> {
+    let mut chess_board = chess_board.clone();
+    // The board before the last move, where its variations start
+    let mut previous_board = chess_board.clone();
+    let mut nodes: Vec<MoveNode> = Vec::new();
+    // A comment at the start of a variation, shown before its first move
+    let mut pre_comment = None;
+
+    while let Some(token) = tokens.get(*position) {
+        match token {
+            Token::Tag(_, _) | Token::CloseVariation => break,
+            Token::Symbol(symbol) if parse_result(symbol).is_some() => break,
+            Token::Symbol(symbol) => {
+                let san = symbol.trim_start_matches(|character: char| character.is_ascii_digit() || character == '.');
+                if !san.is_empty() {
+                    let (san, nag) = split_suffix_annotation(san);
+                    let chess_move = parse_san(&chess_board, san)
+                        .ok_or(format!("Illegal or ambiguous move {} in position {}", symbol, write_fen(&chess_board)))?;
+                    let mut node = MoveNode::new(chess_move);
+                    node.nags.extend(nag);
+                    node.pre_comment = pre_comment.take();
+                    previous_board = chess_board.clone();
+                    chess_board.make_move(&node.chess_move);
+                    nodes.push(node);
+                }
+            }
+            Token::Nag(nag) => {
+                if let Some(node) = nodes.last_mut() {
+                    node.nags.push(*nag);
+                }
+            }
+            Token::Comment(comment) => {
+                match nodes.last_mut() {
+                    Some(node) => append_comment(&mut node.comment, comment),
+                    None => append_comment(&mut pre_comment, comment),
+                }
+            }
+            Token::OpenVariation => {
+                *position += 1;
+                let variation = read_line(&previous_board, tokens, position)?;
+                if tokens.get(*position) != Some(&Token::CloseVariation) {
+                    return Err("Unclosed variation in PGN".to_string());
+                }
+                nodes.last_mut().ok_or("Variation before the first move in PGN")?.variations.push(variation);
+            }
+        }
+        *position += 1;
+    }
+    Ok(nodes)
+}
+
+fn parse_result(token: &str) -> Option<GameResult> {
+    match token {
+        "1-0" => Some(GameResult::WhiteWins),
+        "0-1" => Some(GameResult::BlackWins),
+        "1/2-1/2" => Some(GameResult::Draw),
+        "*" => Some(GameResult::Unknown),
+        _ => None,
+    }
+}
+
+fn split_suffix_annotation(san: &str) -> (&str, Option<u8>) {
+    for (suffix, nag) in SUFFIX_ANNOTATIONS {
+        if let Some(stripped) = san.strip_suffix(suffix) {
+            return (stripped, Some(nag));
+        }
+    }
+    (san, None)
+}
+
+fn append_comment(comment: &mut Option<String>, text: &str) {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    *comment = match comment.take() {
+        Some(existing) => Some(format!("{} {}", existing, text)),
+        None => Some(text),
+    };
+}
+
+fn tokenize(pgn: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut characters = pgn.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(character) = characters.next() {
+        match character {
+            // Lines starting with '%' are escaped and ignored
+            '%' if line_start => {
+                characters.by_ref().find(|&next| next == '\n');
+                continue;
+            }
+            ';' => {
+                let comment: String = characters.by_ref().take_while(|&next| next != '\n').collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+                line_start = true;
+                continue;
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match characters.next() {
+                        Some('}') => break,
+                        Some(next) => comment.push(next),
+                        None => return Err("Unclosed comment in PGN".to_string()),
+                    }
+                }
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            '[' => tokens.push(read_tag(&mut characters)?),
+            '(' => tokens.push(Token::OpenVariation),
+            ')' => tokens.push(Token::CloseVariation),
+            '$' => {
+                let digits = take_symbol(&mut characters, String::new());
+                tokens.push(Token::Nag(digits.parse().map_err(|_| format!("Invalid NAG in PGN: ${}", digits))?));
+            }
+            _ if character.is_whitespace() => {}
+            _ => tokens.push(Token::Symbol(take_symbol(&mut characters, character.to_string()))),
+        }
+        line_start = character == '\n';
+    }
+    Ok(tokens)
+}
+
+// Reads the rest of a symbol, which ends at whitespace or any character with a meaning of its own
+fn take_symbol(characters: &mut std::iter::Peekable<std::str::Chars>, mut symbol: String) -> String {
+    while let Some(&next) = characters.peek() {
+        if next.is_whitespace() || "{}()[];$".contains(next) {
+            break;
+        }
+        symbol.push(next);
+        characters.next();
+    }
+    symbol
+}
+
+// Reads a tag pair after its opening bracket, e.g. `Event "Club championship"]`
+fn read_tag(characters: &mut std::iter::Peekable<std::str::Chars>) -> Result<Token, String> {
+    let name: String = characters.by_ref().skip_while(|next| next.is_whitespace()).take_while(|next| !next.is_whitespace()).collect();
+    if characters.by_ref().find(|next| !next.is_whitespace()) != Some('"') {
+        return Err(format!("Invalid tag pair in PGN: {}", name));
+    }
+    let mut value = String::new();
+    loop {
+        match characters.next() {
+            Some('\\') => value.extend(characters.next()),
+            Some('"') => break,
+            Some(next) => value.push(next),
+            None => return Err(format!("Unclosed tag value in PGN: {}", name)),
+        }
+    }
+    if characters.by_ref().find(|next| !next.is_whitespace()) != Some(']') {
+        return Err(format!("Invalid tag pair in PGN: {}", name));
+    }
+    Ok(Token::Tag(name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::pgn_writer::write_pgn;
+    use crate::utils::san::move_to_san;
+
+    const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 $6 {This is a weak move already.} 4. dxe5 Bxf3 5. Qxf3
+dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5?! (9... Qc7 10. O-O-O) 10. Nxb5! cxb5
+11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8
+17. Rd8# 1-0
+"#;
+
+    #[test]
+    fn test_read_pgn() {
+        let record = read_pgn(OPERA_GAME).unwrap();
+        assert_eq!(Some("Paul Morphy"), record.tag("White"));
+        assert_eq!(GameResult::WhiteWins, record.result);
+        assert_eq!(33, record.moves.len());
+        assert!(record.final_board().is_checkmate());
+
+        let bishop_move = &record.moves[5];
+        assert_eq!(vec![6], bishop_move.nags);
+        assert_eq!(Some("This is a weak move already.".to_string()), bishop_move.comment);
+
+        let pawn_move = &record.moves[17];
+        assert_eq!(vec![6], pawn_move.nags);
+        assert_eq!(1, pawn_move.variations.len());
+        assert_eq!(2, pawn_move.variations[0].len());
+        assert_eq!(vec![1], record.moves[18].nags);
+    }
+
+    #[test]
+    fn test_read_pgn_round_trip() {
+        let record = read_pgn(OPERA_GAME).unwrap();
+        assert_eq!(record, read_pgn(&write_pgn(&record)).unwrap());
+    }
+
+    #[test]
+    fn test_read_pgn_variation_comment() {
+        let record = read_pgn("1. e4 e5 ({Also good} 1... c5 {Sicilian}) 2. Nf3 *").unwrap();
+        let variation = &record.moves[1].variations[0];
+        assert_eq!(Some("Also good".to_string()), variation[0].pre_comment);
+        assert_eq!(Some("Sicilian".to_string()), variation[0].comment);
+        assert_eq!(None, record.moves[1].pre_comment);
+        assert_eq!(record.moves, read_pgn(&write_pgn(&record)).unwrap().moves);
+    }
+
+    #[test]
+    fn test_read_pgn_set_up_position() {
+        let pgn = "[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n[SetUp \"1\"]\n\n{Endgame} 12... Kd7 13.e4 *";
+        let record = read_pgn(pgn).unwrap();
+        assert_eq!("4k3/8/8/8/8/8/4P3/4K3 b - - 0 12", write_fen(&record.starting_board));
+        assert_eq!(Some("Endgame".to_string()), record.comment);
+        let mut chess_board = record.starting_board.clone();
+        chess_board.make_move(&record.moves[0].chess_move);
+        assert_eq!("e4", move_to_san(&chess_board, &record.moves[1].chess_move));
+        assert_eq!(GameResult::Unknown, record.result);
+    }
+
+    #[test]
+    fn test_read_pgn_games() {
+        let pgn = "[Event \"A\"]\n\n1. e4 e5 1/2-1/2\n\n[Event \"B\"]\n\n1. d4 ; a rest of line comment\nd5 0-1\n";
+        let games = read_pgn_games(pgn).unwrap();
+        assert_eq!(2, games.len());
+        assert_eq!(Some("B"), games[1].tag("Event"));
+        assert_eq!(GameResult::BlackWins, games[1].result);
+        assert_eq!(Some("a rest of line comment".to_string()), games[1].moves[0].comment);
+
+        let escaped = read_pgn("% exported by some tool\n% with two lines\n1. e4 *").unwrap();
+        assert_eq!(1, escaped.moves.len());
+    }
+
+    #[test]
+    fn test_read_pgn_errors() {
+        assert!(read_pgn("1. e4 e5 2. Ke3 *").is_err());
+        assert!(read_pgn("1. e4 {unclosed").is_err());
+        assert!(read_pgn("1. e4 (1. d4 *").is_err());
+        assert!(read_pgn("[Event \"A\"\n1. e4 *").is_err());
+        assert!(read_pgn("").is_err());
+    }
+}
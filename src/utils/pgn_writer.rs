@@ -0,0 +1,240 @@
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::game_record::{GameRecord, MoveNode};
+use crate::chess::piece::Color;
+use crate::utils::fen_writer::write_fen;
+use crate::utils::san::move_to_san;
+
+// Functions for writing a GameRecord in Portable Game Notation (PGN).
+// The export format is followed: Seven Tag Roster first, movetext lines of at most 80 characters.
+// PGN has no escape for a closing brace in a comment, so it is written as ')' and does not come
+// back on a round trip.
+
+const MAX_LINE_LENGTH: usize = 80;
+const SEVEN_TAG_ROSTER: [(&str, &str); 6] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+];
+
+pub fn write_pgn(record: &GameRecord) -> String {
+    let mut result = String::new();
+    for (name, value) in tag_pairs(record) {
+        result += &format!("[{} \"{}\"]\n", name, escape_tag_value(&value));
+    }
+    result.push('\n');
+
+    let mut tokens = Vec::new();
+    if let Some(comment) = &record.comment {
+        tokens.push(comment_token(comment));
+    }
+    write_line(&record.starting_board, &record.moves, &mut tokens);
+    tokens.push(record.result.token().to_string());
+
+    result += &wrap_tokens(&tokens);
+    result.push('\n');
+    result
+}
+
+// The Seven Tag Roster in its fixed order, then SetUp and FEN for other starts, then all other tags
+fn tag_pairs(record: &GameRecord) -> Vec<(String, String)> {
+    let mut tags: Vec<(String, String)> = SEVEN_TAG_ROSTER
+        .iter()
+        .map(|(name, default)| (name.to_string(), record.tag(name).unwrap_or(default).to_string()))
+        .collect();
+    tags.push(("Result".to_string(), record.result.token().to_string()));
+
+    let fen = write_fen(&record.starting_board);
+    if fen != write_fen(&ChessBoard::new(false)) {
+        tags.push(("SetUp".to_string(), "1".to_string()));
+        tags.push(("FEN".to_string(), fen));
+    }
+
+    let written = ["Event", "Site", "Date", "Round", "White", "Black", "Result", "SetUp", "FEN"];
+    for (name, value) in &record.tags {
+        if !written.contains(&name.as_str()) {
+            tags.push((name.clone(), value.clone()));
+        }
+    }
+    tags
+}
+
+// Writes the moves of a line starting from the given board, recursing into variations
+fn write_line(chess_board: &ChessBoard, nodes: &[MoveNode], tokens: &mut Vec<String>) {
+    let mut chess_board = chess_board.clone();
+    // Black moves need their number at the start of a line and after comments or variations
+    let mut needs_number = true;
+
+    for node in nodes {
+        if let Some(comment) = &node.pre_comment {
+            tokens.push(comment_token(comment));
+            needs_number = true;
+        }
+        if chess_board.active_color == Color::White {
+            tokens.push(format!("{}.", chess_board.fullmove_number));
+        } else if needs_number {
+            tokens.push(format!("{}...", chess_board.fullmove_number));
+        }
+        tokens.push(move_to_san(&chess_board, &node.chess_move));
+        needs_number = false;
+
+        for nag in &node.nags {
+            tokens.push(format!("${}", nag));
+        }
+        if let Some(comment) = &node.comment {
+            tokens.push(comment_token(comment));
+            needs_number = true;
+        }
+        for variation in &node.variations {
+            tokens.push("(".to_string());
+            write_line(&chess_board, variation, tokens);
+            tokens.push(")".to_string());
+            needs_number = true;
+        }
+
+        chess_board.make_move(&node.chess_move);
+    }
+}
+
+// Comments can not contain a closing brace, as that would end the comment early. It is replaced by
+// ')', so a comment with a '}' is changed by writing it.
+fn comment_token(comment: &str) -> String {
+    format!("{{{}}}", comment.replace('}', ")"))
+}
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Joins the tokens with spaces, without a space after "(" or before ")", and breaks lines at
+// MAX_LINE_LENGTH. Comments are split on their words so they can be wrapped as well.
+fn wrap_tokens(tokens: &[String]) -> String {
+    let mut words: Vec<(String, bool)> = Vec::new();
+    let mut after_open = false;
+    for token in tokens {
+        let attach = after_open || token == ")";
+        if token.starts_with('{') {
+            for (index, word) in token.split_whitespace().enumerate() {
+                words.push((word.to_string(), attach && index == 0));
+            }
+        } else {
+            words.push((token.clone(), attach));
+        }
+        after_open = token == "(";
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for (word, attach) in words {
+        if line.is_empty() {
+            line = word;
+        } else if line.len() + usize::from(!attach) + word.len() > MAX_LINE_LENGTH {
+            lines.push(std::mem::take(&mut line));
+            line = word;
+        } else {
+            if !attach {
+                line.push(' ');
+            }
+            line += &word;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::chess_move::Move;
+    use crate::chess::game_record::GameResult;
+    use crate::chess::piece::PieceType;
+    use crate::utils::board_utils::parse_square;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::pgn_reader::read_pgn;
+
+    fn new_move(piece_type: PieceType, from: &str, to: &str) -> Move {
+        Move::new(piece_type, parse_square(from).unwrap(), parse_square(to).unwrap())
+    }
+
+    #[test]
+    fn test_write_pgn_seven_tag_roster() {
+        let mut record = GameRecord::from_moves(&ChessBoard::new(false), &[
+            new_move(PieceType::Pawn, "f2", "f3"),
+            new_move(PieceType::Pawn, "e7", "e5"),
+            new_move(PieceType::Pawn, "g2", "g4"),
+            new_move(PieceType::Queen, "d8", "h4"),
+        ]);
+        record.set_tag("White", "Fool");
+        record.set_tag("Annotator", "SuperRusty");
+
+        let expected = "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"Fool\"]\n\
+            [Black \"?\"]\n[Result \"0-1\"]\n[Annotator \"SuperRusty\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n";
+        assert_eq!(expected, write_pgn(&record));
+    }
+
+    #[test]
+    fn test_write_pgn_set_up_position() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+        let record = GameRecord::from_moves(&read_fen(fen), &[
+            new_move(PieceType::King, "e8", "d7"),
+            new_move(PieceType::Pawn, "e2", "e4"),
+        ]);
+        let pgn = write_pgn(&record);
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n"));
+        assert!(pgn.ends_with("\n12... Kd7 13. e4 *\n"));
+    }
+
+    #[test]
+    fn test_write_pgn_annotations() {
+        let mut record = GameRecord::new(ChessBoard::new(false));
+        let mut first = MoveNode::new(new_move(PieceType::Pawn, "e2", "e4"));
+        first.nags.push(1);
+        first.comment = Some("Best by test".to_string());
+        let mut second = MoveNode::new(new_move(PieceType::Pawn, "c7", "c5"));
+        let mut alternative = MoveNode::new(new_move(PieceType::Pawn, "e7", "e5"));
+        alternative.pre_comment = Some("Classical".to_string());
+        second.variations.push(vec![alternative, MoveNode::new(new_move(PieceType::Knight, "g1", "f3"))]);
+        record.moves = vec![first, second, MoveNode::new(new_move(PieceType::Knight, "g1", "f3"))];
+        record.result = GameResult::Draw;
+
+        let pgn = write_pgn(&record);
+        assert!(pgn.ends_with("\n1. e4 $1 {Best by test} 1... c5 ({Classical} 1... e5 2. Nf3) 2. Nf3 1/2-1/2\n"));
+    }
+
+    #[test]
+    fn test_write_pgn_wraps_lines() {
+        let mut chess_board = ChessBoard::new(false);
+        let mut moves = Vec::new();
+        // Shuffle the knights back and forth to get a long movetext
+        for _ in 0..10 {
+            for (from, to) in [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")] {
+                let chess_move = new_move(PieceType::Knight, from, to);
+                chess_board.make_move(&chess_move);
+                moves.push(chess_move);
+            }
+        }
+        let pgn = write_pgn(&GameRecord::from_moves(&ChessBoard::new(false), &chess_board.move_history));
+        let movetext = pgn.split("\n\n").nth(1).unwrap();
+        assert!(movetext.lines().count() > 1);
+        assert!(movetext.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        let read_moves: Vec<Move> = read_pgn(&pgn).unwrap().moves.into_iter().map(|node| node.chess_move).collect();
+        assert_eq!(moves, read_moves);
+    }
+
+    #[test]
+    fn test_write_pgn_replaces_closing_brace_in_comment() {
+        let mut record = GameRecord::new(ChessBoard::new(false));
+        let mut first = MoveNode::new(new_move(PieceType::Pawn, "e2", "e4"));
+        first.comment = Some("A {nested} comment".to_string());
+        record.moves = vec![first];
+
+        let pgn = write_pgn(&record);
+        assert!(pgn.ends_with("\n1. e4 {A {nested) comment} *\n"));
+        // The brace is lost on a round trip
+        assert_eq!(Some("A {nested) comment".to_string()), read_pgn(&pgn).unwrap().moves[0].comment);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::{ChessBoard, BLACK_KING_SIDE_ROOK, BLACK_KING_START, BLACK_QUEEN_SIDE_ROOK, WHITE_KING_SIDE_ROOK, WHITE_KING_START, WHITE_QUEEN_SIDE_ROOK};
+use crate::chess::piece::{Color, PieceType};
+use crate::utils::board_utils::{square_name, RANK_EIGHT, RANK_ONE, RANK_SIX, RANK_THREE};
+
+// Checks that a position could be reached in a game, as far as that can be told without looking
+// at the moves that led to it. Positions read from outside sources or put together piece by piece
+// can be checked with this before they are searched.
+
+/// Returns an error message describing the first problem found with the position, if any.
+pub fn validate_position(chess_board: &ChessBoard) -> Result<(), String> {
+    let mut occupied = 0;
+    for color in [Color::White, Color::Black] {
+        for piece_type in PieceType::ALL {
+            let positions = chess_board.get_piece_info(piece_type, color).positions;
+            if occupied & positions != 0 {
+                return Err(format!("More than one piece on {}", square_name(lowest_square(occupied & positions))));
+            }
+            occupied |= positions;
+        }
+        validate_material(chess_board, color)?;
+    }
+
+    let pawns = chess_board.get_piece_info(PieceType::Pawn, Color::White).positions | chess_board.get_piece_info(PieceType::Pawn, Color::Black).positions;
+    if pawns & (RANK_ONE | RANK_EIGHT) != 0 {
+        return Err(format!("Pawn on the first or last rank on {}", square_name(lowest_square(pawns & (RANK_ONE | RANK_EIGHT)))));
+    }
+
+    let color = chess_board.active_color;
+    if chess_board.is_square_attacked(chess_board.king_square(!color), color) {
+        return Err(format!("{:?} is in check, but it is {:?} to move", !color, color));
+    }
+    // No single move uncovers more than two checks
+    if chess_board.checkers().count_ones() > 2 {
+        return Err(format!("{:?} is in check by more than two pieces", color));
+    }
+
+    validate_castling_rights(chess_board)?;
+    validate_en_passant(chess_board)
+}
+
+fn validate_material(chess_board: &ChessBoard, color: Color) -> Result<(), String> {
+    let count = |piece_type: PieceType| chess_board.get_piece_info(piece_type, color).positions.count_ones();
+    if count(PieceType::King) != 1 {
+        return Err(format!("{:?} has {} kings", color, count(PieceType::King)));
+    }
+    if count(PieceType::Pawn) > 8 {
+        return Err(format!("{:?} has {} pawns", color, count(PieceType::Pawn)));
+    }
+    // Pieces beyond the ones of the starting position must be promoted pawns
+    let promoted = count(PieceType::Knight).saturating_sub(2)
+        + count(PieceType::Bishop).saturating_sub(2)
+        + count(PieceType::Rook).saturating_sub(2)
+        + count(PieceType::Queen).saturating_sub(1);
+    if promoted + count(PieceType::Pawn) > 8 {
+        return Err(format!("{:?} has more promoted pieces than missing pawns", color));
+    }
+    Ok(())
+}
+
+fn validate_castling_rights(chess_board: &ChessBoard) -> Result<(), String> {
+    let rights = [
+        (Color::White, Castling::KingSide, WHITE_KING_START, WHITE_KING_SIDE_ROOK),
+        (Color::White, Castling::QueenSide, WHITE_KING_START, WHITE_QUEEN_SIDE_ROOK),
+        (Color::Black, Castling::KingSide, BLACK_KING_START, BLACK_KING_SIDE_ROOK),
+        (Color::Black, Castling::QueenSide, BLACK_KING_START, BLACK_QUEEN_SIDE_ROOK),
+    ];
+    for (color, castling, king, rook) in rights {
+        if !chess_board.has_castling_right(color, castling) {
+            continue;
+        }
+        let in_place = chess_board.get_piece_info(PieceType::King, color).positions & king != 0
+            && chess_board.get_piece_info(PieceType::Rook, color).positions & rook != 0;
+        if !in_place {
+            return Err(format!("{:?} can castle {:?}, but the king or rook has moved", color, castling));
+        }
+    }
+    Ok(())
+}
+
+// The en passant square is set after every double push, so it needs the pawn that was just pushed
+// and the two empty squares it passed, but no pawn that can take it.
+fn validate_en_passant(chess_board: &ChessBoard) -> Result<(), String> {
+    let square = chess_board.en_passant;
+    if square == 0 {
+        return Ok(());
+    }
+    let (rank, pushed, start) = match chess_board.active_color {
+        Color::White => (RANK_SIX, square >> 8, square << 8),
+        Color::Black => (RANK_THREE, square << 8, square >> 8),
+    };
+    let opponent_pawns = chess_board.get_piece_info(PieceType::Pawn, !chess_board.active_color).positions;
+    if square.count_ones() != 1 || square & rank == 0 || opponent_pawns & pushed == 0 || chess_board.occupancy() & (square | start) != 0 {
+        return Err(format!("No pawn can have been pushed over the en passant square {}", square_name(square)));
+    }
+    Ok(())
+}
+
+fn lowest_square(bitboard: u64) -> u64 {
+    bitboard & bitboard.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fen_reader::read_fen;
+
+    #[test]
+    fn test_valid_positions() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "4k3/8/8/8/8/8/8/QQQQKQQQ b - - 0 1",
+            // Double check by a knight and a bishop
+            "4k3/8/3N4/1B6/8/8/8/4K3 b - - 0 1",
+        ];
+        for fen in fens {
+            assert_eq!(Ok(()), validate_position(&read_fen(fen)), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_invalid_positions() {
+        let fens = [
+            ("8/8/8/8/8/8/8/4K3 w - - 0 1", "Black has 0 kings"),
+            ("4k3/8/8/8/8/8/8/3KK3 w - - 0 1", "White has 2 kings"),
+            ("4k3/8/8/8/8/8/8/P3K3 w - - 0 1", "Pawn on the first or last rank on a1"),
+            ("4k3/pppppppp/p7/8/8/8/8/4K3 w - - 0 1", "Black has 9 pawns"),
+            ("4k3/8/8/8/8/8/1PPPPPPP/QQQ1K3 w - - 0 1", "White has more promoted pieces than missing pawns"),
+            ("4k2R/8/8/8/8/8/8/4K3 w - - 0 1", "Black is in check, but it is White to move"),
+            ("4k3/4r3/8/8/8/8/8/4K3 b - - 0 1", "White is in check, but it is Black to move"),
+            ("1r2k3/8/8/8/8/8/8/4K3 b q - 0 1", "Black can castle QueenSide, but the king or rook has moved"),
+            ("4k3/8/8/8/8/8/8/4K2R w Q - 0 1", "White can castle QueenSide, but the king or rook has moved"),
+            ("4k3/8/8/8/8/8/8/4K3 w - e6 0 1", "No pawn can have been pushed over the en passant square e6"),
+            ("4k3/8/8/4p3/8/8/8/4K3 w - e3 0 1", "No pawn can have been pushed over the en passant square e3"),
+        ];
+        for (fen, error) in fens {
+            assert_eq!(Err(error.to_string()), validate_position(&read_fen(fen)), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_overlapping_pieces() {
+        let mut chess_board = read_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        chess_board.get_mutable_piece_info(PieceType::Queen, Color::White).positions |= chess_board.king_square(Color::Black);
+        assert_eq!(Err("More than one piece on e8".to_string()), validate_position(&chess_board));
+    }
+
+    #[test]
+    fn test_triple_check() {
+        let chess_board = read_fen("4k3/8/3N4/1B6/8/8/8/4RK2 b - - 0 1");
+        assert_eq!(Err("Black is in check by more than two pieces".to_string()), validate_position(&chess_board));
+    }
+}
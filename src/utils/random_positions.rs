@@ -0,0 +1,191 @@
+use rand::Rng;
+
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::piece::{Color, PieceType};
+use crate::utils::board_utils::{squares, RANK_EIGHT, RANK_ONE};
+use crate::utils::epd::{write_epd, EpdRecord};
+use crate::utils::fen_writer::write_fen;
+use crate::utils::position_validator::validate_position;
+
+// Random positions and games for tests, fuzzing and training data. All functions take the random
+// number generator as an argument, so a seeded generator gives the same positions every run.
+
+// Some signatures only have a few legal placements, e.g. with many pawns, so give up eventually
+const MAX_PLACEMENT_ATTEMPTS: usize = 10_000;
+
+/// Reads a material signature such as `KRPvKR`, the white pieces before the `v` and the black
+/// pieces after it. Each side needs exactly one king, at most 8 pawns and at most 16 pieces.
+pub fn parse_material_signature(signature: &str) -> Result<[Vec<PieceType>; 2], String> {
+    let Some((white, black)) = signature.split_once(['v', 'V']) else {
+        return Err(format!("Material signature needs a 'v' between the two sides: {}", signature));
+    };
+    let parse_side = |side: &str| -> Result<Vec<PieceType>, String> {
+        let pieces = side
+            .chars()
+            .map(|letter| PieceType::from_letter(letter).ok_or(format!("Invalid piece '{}' in material signature: {}", letter, signature)))
+            .collect::<Result<Vec<PieceType>, String>>()?;
+        let count = |piece_type: PieceType| pieces.iter().filter(|&&piece| piece == piece_type).count();
+        if count(PieceType::King) != 1 {
+            return Err(format!("Each side needs exactly one king in material signature: {}", signature));
+        }
+        // More would not fit on the board, or could never come from the pieces of one side
+        if count(PieceType::Pawn) > 8 || pieces.len() > 16 {
+            return Err(format!("Each side can have at most 8 pawns and 16 pieces in material signature: {}", signature));
+        }
+        Ok(pieces)
+    };
+    Ok([parse_side(white)?, parse_side(black)?])
+}
+
+/// Places the pieces of the material signature on random squares until the position is legal for
+/// `active_color` to move. The position has no castling rights and no en passant square.
+pub fn random_position(signature: &str, active_color: Color, rng: &mut impl Rng) -> Result<ChessBoard, String> {
+    let [white, black] = parse_material_signature(signature)?;
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+        let mut chess_board = ChessBoard::new(true);
+        chess_board.clear_castling();
+        chess_board.set_active_color(active_color);
+        for (color, pieces) in [(Color::White, &white), (Color::Black, &black)] {
+            for &piece_type in pieces {
+                let square = random_empty_square(&chess_board, piece_type, rng);
+                chess_board.get_mutable_piece_info(piece_type, color).positions |= square;
+            }
+        }
+        if validate_position(&chess_board).is_ok() {
+            return Ok(chess_board);
+        }
+    }
+    Err(format!("No legal position found for material signature {} with {:?} to move", signature, active_color))
+}
+
+// Pawns are never placed on the first or last rank. The signature allows at most 32 pieces and
+// 16 pawns, so there is always a free square left.
+fn random_empty_square(chess_board: &ChessBoard, piece_type: PieceType, rng: &mut impl Rng) -> u64 {
+    let free = match piece_type {
+        PieceType::Pawn => !chess_board.occupancy() & !(RANK_ONE | RANK_EIGHT),
+        _ => !chess_board.occupancy(),
+    };
+    squares(free).nth(rng.gen_range(0..free.count_ones() as usize)).unwrap()
+}
+
+/// Plays up to `plies` random legal moves from the position and returns every position on the
+/// way, starting with the given one. Stops early when the game is over by checkmate or stalemate.
+pub fn random_playout(chess_board: &ChessBoard, plies: usize, rng: &mut impl Rng) -> Vec<ChessBoard> {
+    let mut positions = vec![chess_board.clone()];
+    let mut position = chess_board.clone();
+    for _ in 0..plies {
+        let legal_moves = position.legal_moves();
+        if legal_moves.is_empty() {
+            break;
+        }
+        position.make_move(&legal_moves[rng.gen_range(0..legal_moves.len())]);
+        positions.push(position.clone());
+    }
+    positions
+}
+
+/// Writes one FEN string per line.
+pub fn write_fen_lines(positions: &[ChessBoard]) -> String {
+    positions.iter().map(|chess_board| write_fen(chess_board) + "\n").collect()
+}
+
+/// Writes one EPD record without operations per line.
+pub fn write_epd_lines(positions: &[ChessBoard]) -> String {
+    positions.iter().map(|chess_board| write_epd(&EpdRecord::new(chess_board.clone())) + "\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::epd::read_epd_lines;
+    use crate::utils::fen_reader::read_fen;
+    use crate::utils::fen_writer::position_fields;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_material_signature() {
+        assert_eq!(
+            [vec![PieceType::King, PieceType::Rook, PieceType::Pawn], vec![PieceType::King, PieceType::Rook]],
+            parse_material_signature("KRPvKR").unwrap()
+        );
+        assert!(parse_material_signature("KRPKR").is_err());
+        assert!(parse_material_signature("KXvK").is_err());
+        assert!(parse_material_signature("KRvR").is_err());
+        assert!(parse_material_signature("KKvK").is_err());
+        // Too many pawns or pieces for one side
+        assert!(parse_material_signature("KPPPPPPPPPvK").is_err());
+        assert!(parse_material_signature(&format!("K{}vK", "Q".repeat(16))).is_err());
+        assert!(parse_material_signature(&format!("K{}vK", "Q".repeat(15))).is_ok());
+    }
+
+    #[test]
+    fn test_random_position() {
+        let mut rng = StdRng::seed_from_u64(47);
+        for (signature, active_color) in [("KRPvKR", Color::White), ("KQvK", Color::Black), ("KPPPPPPPPvKNNBBRRQ", Color::White)] {
+            for _ in 0..20 {
+                let chess_board = random_position(signature, active_color, &mut rng).unwrap();
+                assert_eq!(Ok(()), validate_position(&chess_board));
+                assert_eq!(active_color, chess_board.active_color);
+                let count = |color: Color| chess_board.pieces_of(color).count_ones() as usize;
+                let [white, black] = parse_material_signature(signature).unwrap();
+                assert_eq!((white.len(), black.len()), (count(Color::White), count(Color::Black)));
+                assert_eq!(
+                    white.iter().filter(|&&piece_type| piece_type == PieceType::Pawn).count() as u32,
+                    chess_board.get_piece_info(PieceType::Pawn, Color::White).positions.count_ones()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_position_full_board() {
+        // 32 pieces, 16 of them pawns, still leave free squares for every piece
+        let signature = "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP";
+        let chess_board = random_position(signature, Color::White, &mut StdRng::seed_from_u64(47)).unwrap();
+        assert_eq!(32, chess_board.occupancy().count_ones());
+    }
+
+    #[test]
+    fn test_random_position_is_seeded() {
+        let first = random_position("KRPvKR", Color::White, &mut StdRng::seed_from_u64(1)).unwrap();
+        let second = random_position("KRPvKR", Color::White, &mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(write_fen(&first), write_fen(&second));
+    }
+
+    #[test]
+    fn test_random_playout() {
+        let start = ChessBoard::new(false);
+        let positions = random_playout(&start, 30, &mut StdRng::seed_from_u64(47));
+        assert!(positions.len() <= 31);
+        assert_eq!(start, positions[0]);
+        for pair in positions.windows(2) {
+            assert_eq!(Ok(()), validate_position(&pair[1]));
+            let last_move = pair[1].move_history.last().unwrap();
+            assert!(pair[0].legal_moves().contains(last_move));
+        }
+        let again = random_playout(&start, 30, &mut StdRng::seed_from_u64(47));
+        assert_eq!(positions, again);
+
+        // A checkmate ends the playout
+        let mate = read_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(1, random_playout(&mate, 10, &mut StdRng::seed_from_u64(47)).len());
+    }
+
+    #[test]
+    fn test_write_lines() {
+        let positions = random_playout(&ChessBoard::new(false), 6, &mut StdRng::seed_from_u64(47));
+        let fens = write_fen_lines(&positions);
+        assert_eq!(positions.len(), fens.lines().count());
+        assert_eq!("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", fens.lines().next().unwrap());
+        for (fen, chess_board) in fens.lines().zip(&positions) {
+            assert_eq!(write_fen(chess_board), write_fen(&read_fen(fen)));
+        }
+
+        let records = read_epd_lines(&write_epd_lines(&positions)).unwrap();
+        assert_eq!(positions.len(), records.len());
+        for (record, chess_board) in records.iter().zip(&positions) {
+            assert_eq!(position_fields(chess_board), position_fields(&record.chess_board));
+        }
+    }
+}
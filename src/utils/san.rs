@@ -0,0 +1,427 @@
+use crate::chess::castling::Castling;
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::Move;
+use crate::chess::piece::PieceType;
+use crate::chess::visualization::PIECE_CHARACTERS;
+use crate::utils::board_utils::{parse_square, square_name, square_to_xy};
+
+// Functions for reading and writing moves in Standard Algebraic Notation (SAN), e.g. Nf3, exd5, O-O
+// or e8=Q+. Moves are always interpreted in a given position, which is used for captures,
+// disambiguation and checks. PGN and EPD always use the English piece letters, other styles are
+// meant for showing moves to users.
+
+/// The piece letters used in SAN. Pawns have no letter in SAN, so only the other pieces differ.
+#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+pub enum SanStyle {
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Dutch,
+    // The figurines of the white pieces, as used by `Visualize`: ♘ ♗ ♖ ♕ ♔
+    Figurine,
+}
+
+impl SanStyle {
+    /// The letter or figurine used for a piece, pawns return the English letter.
+    pub fn letter(self, piece_type: PieceType) -> &'static str {
+        // Knight, bishop, rook, queen and king
+        let letters = match self {
+            SanStyle::English => ["N", "B", "R", "Q", "K"],
+            SanStyle::German => ["S", "L", "T", "D", "K"],
+            SanStyle::French => ["C", "F", "T", "D", "R"],
+            SanStyle::Spanish | SanStyle::Italian => ["C", "A", "T", "D", "R"],
+            SanStyle::Dutch => ["P", "L", "T", "D", "K"],
+            SanStyle::Figurine => ["♘", "♗", "♖", "♕", "♔"],
+        };
+        match piece_type {
+            PieceType::Pawn => "P",
+            PieceType::Knight => letters[0],
+            PieceType::Bishop => letters[1],
+            PieceType::Rook => letters[2],
+            PieceType::Queen => letters[3],
+            PieceType::King => letters[4],
+        }
+    }
+
+    // Finds the piece of a letter in this style, figurines of both colors are accepted for every style
+    fn piece_type(self, letter: char) -> Option<PieceType> {
+        let pieces = [PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen, PieceType::King];
+        let mut buffer = [0; 4];
+        let letter_string: &str = letter.encode_utf8(&mut buffer);
+        pieces
+            .into_iter()
+            .find(|&piece_type| self.letter(piece_type) == letter_string)
+            .or_else(|| PIECE_CHARACTERS
+                .iter()
+                .find(|(_, character)| character.starts_with(letter))
+                .map(|((piece_type, _), _)| *piece_type)
+                .filter(|&piece_type| piece_type != PieceType::Pawn))
+    }
+}
+
+pub fn move_to_san(chess_board: &ChessBoard, chess_move: &Move) -> String {
+    let mut result = san_without_suffix(chess_board, chess_move);
+
+    // Add + for check and # for checkmate
+    let mut next = chess_board.clone();
+    next.make_move(chess_move);
+    if next.is_in_check() {
+        result.push(if next.legal_moves().is_empty() { '#' } else { '+' });
+    }
+    result
+}
+
+fn san_without_suffix(chess_board: &ChessBoard, chess_move: &Move) -> String {
+    let from = chess_move.old_position;
+    let to = chess_move.new_position;
+
+    if chess_move.piece_type == PieceType::King {
+        if from == to << 2 {
+            return "O-O".to_string();
+        }
+        if from == to >> 2 {
+            return "O-O-O".to_string();
+        }
+    }
+
+    let is_capture = chess_board.occupancy() & to != 0
+        || (chess_move.piece_type == PieceType::Pawn && to == chess_board.en_passant);
+    let mut result = String::new();
+
+    if chess_move.piece_type == PieceType::Pawn {
+        // Pawn captures are written with the file the pawn came from
+        if is_capture {
+            result.push(square_name(from).chars().next().unwrap());
+        }
+    } else {
+        result.push(chess_move.piece_type.letter());
+        result += &disambiguation(chess_board, chess_move);
+    }
+
+    if is_capture {
+        result.push('x');
+    }
+    result += &square_name(to);
+
+    if let Some(promotion) = chess_move.promotion {
+        result.push('=');
+        result.push(promotion.letter());
+    }
+    result
+}
+
+/// Finds the legal move matching a SAN string in the given position. Check and annotation suffixes
+/// such as `+`, `#`, `!` or `?!` are ignored. Returns None if no move or more than one move matches.
+pub fn parse_san(chess_board: &ChessBoard, san: &str) -> Option<Move> {
+    let candidates = san_candidates(chess_board, san);
+    if candidates.len() == 1 {
+        candidates.into_iter().next()
+    } else {
+        None
+    }
+}
+
+/// Writes a move in SAN with the piece letters of the given style, e.g. `Sf3` in German or `♘f3`.
+pub fn move_to_localized_san(chess_board: &ChessBoard, chess_move: &Move, style: SanStyle) -> String {
+    let san = move_to_san(chess_board, chess_move);
+    if style == SanStyle::English {
+        return san;
+    }
+    // Piece letters only appear at the start (the moving piece) and after '=' (the promotion)
+    let mut result = String::new();
+    let mut previous = None;
+    for (index, character) in san.chars().enumerate() {
+        match PieceType::from_letter(character) {
+            Some(piece_type) if character != 'O' && (index == 0 || previous == Some('=')) => {
+                result += style.letter(piece_type)
+            }
+            _ => result.push(character),
+        }
+        previous = Some(character);
+    }
+    result
+}
+
+/// Finds the legal move matching a SAN string written with the piece letters of the given style.
+/// Figurines are accepted in every style.
+pub fn parse_localized_san(chess_board: &ChessBoard, san: &str, style: SanStyle) -> Option<Move> {
+    parse_san(chess_board, &english_san(san, style))
+}
+
+// Translates the piece letters of a localized SAN string back to English
+fn english_san(san: &str, style: SanStyle) -> String {
+    let characters: Vec<char> = san.trim_end_matches(['+', '#', '!', '?']).chars().collect();
+    let mut result = String::new();
+    for (index, &character) in characters.iter().enumerate() {
+        // A promotion piece follows '=' or directly follows the rank of the target square
+        let is_piece_position = index == 0
+            || characters[index - 1] == '='
+            || (index == characters.len() - 1 && characters[index - 1].is_ascii_digit());
+        match style.piece_type(character) {
+            Some(piece_type) if is_piece_position && character != 'O' => result.push(piece_type.letter()),
+            _ => result.push(character),
+        }
+    }
+    result
+}
+
+// The parts of a SAN string, before they are matched against the moves of a position
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum SanParts {
+    Castling(Castling),
+    Move {
+        piece_type: PieceType,
+        target: u64,
+        promotion: Option<PieceType>,
+        // The file and rank given to tell apart pieces that can reach the same target
+        file: Option<usize>,
+        rank: Option<usize>,
+    },
+}
+
+impl SanParts {
+    // Returns true if the move fits the SAN, whether it is legal or not
+    pub(crate) fn matches(&self, chess_move: &Move) -> bool {
+        match *self {
+            SanParts::Castling(castling) => {
+                let target = match castling {
+                    Castling::KingSide => chess_move.old_position >> 2,
+                    Castling::QueenSide => chess_move.old_position << 2,
+                };
+                chess_move.piece_type == PieceType::King && chess_move.new_position == target
+            }
+            SanParts::Move { piece_type, target, promotion, file, rank } => {
+                let (x, y) = square_to_xy(chess_move.old_position);
+                chess_move.piece_type == piece_type
+                    && chess_move.new_position == target
+                    && chess_move.promotion == promotion
+                    && file.is_none_or(|file| file == x)
+                    && rank.is_none_or(|rank| rank == y)
+            }
+        }
+    }
+}
+
+// Splits a SAN string into its parts, ignoring check and annotation suffixes. Returns None if the
+// string is not SAN.
+pub(crate) fn parse_san_parts(san: &str) -> Option<SanParts> {
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+
+    // Castling, also accepting the zeros used by some programs
+    match san {
+        "O-O" | "0-0" => return Some(SanParts::Castling(Castling::KingSide)),
+        "O-O-O" | "0-0-0" => return Some(SanParts::Castling(Castling::QueenSide)),
+        _ => {}
+    }
+
+    let mut characters: Vec<char> = san.chars().collect();
+
+    // A promotion is written as e8=Q or e8Q
+    let mut promotion = None;
+    if let Some(&last) = characters.last() {
+        if let Some(piece_type) = PieceType::from_letter(last).filter(|_| last.is_ascii_uppercase()) {
+            promotion = Some(piece_type);
+            characters.pop();
+            if characters.last() == Some(&'=') {
+                characters.pop();
+            }
+        }
+    }
+
+    // The target square is always the last two characters
+    if characters.len() < 2 {
+        return None;
+    }
+    let target: String = characters[characters.len() - 2..].iter().collect();
+    let target = parse_square(&target)?;
+    characters.truncate(characters.len() - 2);
+
+    // An upper case letter at the start is the piece, without one it is a pawn move
+    let mut piece_type = PieceType::Pawn;
+    if let Some(&first) = characters.first() {
+        if first.is_ascii_uppercase() {
+            piece_type = PieceType::from_letter(first)?;
+            characters.remove(0);
+        }
+    }
+
+    // What remains is the optional disambiguation and capture sign
+    let mut file = None;
+    let mut rank = None;
+    for character in characters {
+        match character {
+            'a'..='h' => file = Some(character as usize - 'a' as usize),
+            '1'..='8' => rank = Some(character as usize - '1' as usize),
+            'x' | ':' | '-' => {}
+            _ => return None,
+        }
+    }
+    Some(SanParts::Move { piece_type, target, promotion, file, rank })
+}
+
+// Returns all legal moves that match the SAN string, more than one if the SAN is ambiguous
+pub(crate) fn san_candidates(chess_board: &ChessBoard, san: &str) -> Vec<Move> {
+    let Some(parts) = parse_san_parts(san) else {
+        return Vec::new();
+    };
+    chess_board.legal_moves().into_iter().filter(|chess_move| parts.matches(chess_move)).collect()
+}
+
+// When several pieces of the same type can reach the target, add the file, the rank or both
+fn disambiguation(chess_board: &ChessBoard, chess_move: &Move) -> String {
+    let others: Vec<u64> = chess_board
+        .legal_moves()
+        .into_iter()
+        .filter(|other| other.piece_type == chess_move.piece_type
+            && other.new_position == chess_move.new_position
+            && other.old_position != chess_move.old_position)
+        .map(|other| other.old_position)
+        .collect();
+
+    if others.is_empty() {
+        return String::new();
+    }
+
+    let (x, y) = square_to_xy(chess_move.old_position);
+    let name = square_name(chess_move.old_position);
+    if others.iter().all(|&other| square_to_xy(other).0 != x) {
+        name[..1].to_string()
+    } else if others.iter().all(|&other| square_to_xy(other).1 != y) {
+        name[1..].to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movesets::test_positions::KIWIPETE;
+    use crate::utils::fen_reader::read_fen;
+
+    fn san(fen: &str, piece_type: PieceType, from: &str, to: &str) -> String {
+        let chess_move = Move::new(piece_type, parse_square(from).unwrap(), parse_square(to).unwrap());
+        move_to_san(&read_fen(fen), &chess_move)
+    }
+
+    #[test]
+    fn test_move_to_san_simple_moves() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!("e4", san(start, PieceType::Pawn, "e2", "e4"));
+        assert_eq!("Nf3", san(start, PieceType::Knight, "g1", "f3"));
+    }
+
+    #[test]
+    fn test_move_to_san_captures() {
+        let fen = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2";
+        assert_eq!("exd5", san(fen, PieceType::Pawn, "e4", "d5"));
+        let en_passant = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+        assert_eq!("exf6", san(en_passant, PieceType::Pawn, "e5", "f6"));
+    }
+
+    #[test]
+    fn test_move_to_san_castling() {
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!("O-O", san(fen, PieceType::King, "e1", "g1"));
+        assert_eq!("O-O-O", san(fen, PieceType::King, "e1", "c1"));
+    }
+
+    #[test]
+    fn test_move_to_san_disambiguation() {
+        let fen = "4k3/8/8/8/8/8/4K3/R6R w - - 0 1";
+        assert_eq!("Rhf1", san(fen, PieceType::Rook, "h1", "f1"));
+        let fen = "4k3/8/8/8/R7/8/8/R3K3 w - - 0 1";
+        assert_eq!("R1a2", san(fen, PieceType::Rook, "a1", "a2"));
+        let fen = "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1";
+        assert_eq!("Qa1b2", san(fen, PieceType::Queen, "a1", "b2"));
+    }
+
+    #[test]
+    fn test_parse_san() {
+        let chess_board = read_fen(KIWIPETE);
+        let parse = |san: &str| parse_san(&chess_board, san).map(|chess_move| chess_move.to_uci());
+        assert_eq!(Some("e1g1".to_string()), parse("O-O"));
+        assert_eq!(Some("e1c1".to_string()), parse("0-0-0"));
+        assert_eq!(Some("e5f7".to_string()), parse("Nxf7"));
+        assert_eq!(Some("d5e6".to_string()), parse("dxe6!?"));
+        assert_eq!(Some("a2a4".to_string()), parse("a4"));
+        assert_eq!(Some("c3b1".to_string()), parse("Ncb1"));
+        assert_eq!(Some("c3b1".to_string()), parse("Nb1"));
+        assert_eq!(None, parse("Qh8"));
+        assert_eq!(None, parse("Zf3"));
+        // Two rooks can go to f1
+        let ambiguous = read_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1");
+        assert_eq!(None, parse_san(&ambiguous, "Rf1"));
+        assert_eq!(2, san_candidates(&ambiguous, "Rf1").len());
+    }
+
+    #[test]
+    fn test_parse_san_promotion() {
+        let chess_board = read_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1");
+        let promotion = parse_san(&chess_board, "gxf1=N").unwrap();
+        assert_eq!("g2f1n", promotion.to_uci());
+        assert_eq!(Some(promotion), parse_san(&chess_board, "gxf1N"));
+    }
+
+    #[test]
+    fn test_parse_san_round_trip() {
+        let chess_board = read_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1");
+        for chess_move in chess_board.legal_moves() {
+            let san = move_to_san(&chess_board, &chess_move);
+            assert_eq!(Some(chess_move), parse_san(&chess_board, &san), "{}", san);
+        }
+    }
+
+    #[test]
+    fn test_move_to_localized_san() {
+        let chess_board = read_fen(KIWIPETE);
+        let knight_capture = parse_san(&chess_board, "Nxf7").unwrap();
+        assert_eq!("Sxf7", move_to_localized_san(&chess_board, &knight_capture, SanStyle::German));
+        assert_eq!("Cxf7", move_to_localized_san(&chess_board, &knight_capture, SanStyle::French));
+        assert_eq!("♘xf7", move_to_localized_san(&chess_board, &knight_capture, SanStyle::Figurine));
+
+        let castling = parse_san(&chess_board, "O-O").unwrap();
+        assert_eq!("O-O", move_to_localized_san(&chess_board, &castling, SanStyle::German));
+        let pawn_capture = parse_san(&chess_board, "dxe6").unwrap();
+        assert_eq!("dxe6", move_to_localized_san(&chess_board, &pawn_capture, SanStyle::Spanish));
+        let bishop_move = parse_san(&chess_board, "Bxa6").unwrap();
+        assert_eq!("Lxa6", move_to_localized_san(&chess_board, &bishop_move, SanStyle::German));
+    }
+
+    #[test]
+    fn test_localized_promotion() {
+        let chess_board = read_fen("8/P7/8/8/8/8/8/2K4k w - - 0 1");
+        let promotion = parse_san(&chess_board, "a8=Q").unwrap();
+        assert_eq!("a8=D+", move_to_localized_san(&chess_board, &promotion, SanStyle::German));
+        assert_eq!("a8=♕+", move_to_localized_san(&chess_board, &promotion, SanStyle::Figurine));
+        assert_eq!(Some(promotion.clone()), parse_localized_san(&chess_board, "a8=D+", SanStyle::German));
+        assert_eq!(Some(promotion.clone()), parse_localized_san(&chess_board, "a8D", SanStyle::French));
+        assert_eq!(Some(promotion), parse_localized_san(&chess_board, "a8♛", SanStyle::English));
+    }
+
+    #[test]
+    fn test_parse_localized_san_round_trip() {
+        let chess_board = read_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1");
+        let styles = [SanStyle::German, SanStyle::French, SanStyle::Spanish, SanStyle::Italian, SanStyle::Dutch, SanStyle::Figurine];
+        for style in styles {
+            for chess_move in chess_board.legal_moves() {
+                let san = move_to_localized_san(&chess_board, &chess_move, style);
+                assert_eq!(Some(chess_move), parse_localized_san(&chess_board, &san, style), "{}", san);
+            }
+        }
+        // In French R is the king, not the rook
+        let king = read_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        assert_eq!(PieceType::King, parse_localized_san(&king, "Rf1", SanStyle::French).unwrap().piece_type);
+    }
+
+    #[test]
+    fn test_move_to_san_promotion_and_checks() {
+        let fen = "8/P7/8/8/8/8/8/2K4k w - - 0 1";
+        let promotion = Move::new_promotion(parse_square("a7").unwrap(), parse_square("a8").unwrap(), PieceType::Queen);
+        assert_eq!("a8=Q+", move_to_san(&read_fen(fen), &promotion));
+        let fools_mate = "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2";
+        assert_eq!("Qh4#", san(fools_mate, PieceType::Queen, "d8", "h4"));
+    }
+}
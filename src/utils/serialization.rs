@@ -0,0 +1,249 @@
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::chess::chess_board::ChessBoard;
+use crate::chess::chess_move::{parse_uci, Move};
+use crate::chess::game_record::{GameRecord, GameResult, MoveNode};
+use crate::chess::piece::PieceType;
+use crate::utils::fen_reader::try_read_fen;
+use crate::utils::fen_writer::write_fen;
+
+// Serde support, enabled with the `serde` feature. The JSON shapes are meant to be readable:
+//
+// ChessBoard   "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1" (the move history is not kept)
+// Move         {"piece_type": "Knight", "uci": "g1f3"}
+// GameRecord   {"tags": {"White": "Morphy"}, "starting_position": "<FEN>", "moves": [{"uci": "e2e4"}],
+//               "result": "WhiteWins"}
+//
+// Color, PieceType, Castling and GameResult are written as their variant names. The moves of a game
+// record are plain UCI strings, they are checked against the position when they are read. A lone
+// move has no position to find the moving piece in, as UCI does not name it, so it is an object that
+// keeps the piece type next to the UCI string.
+
+impl Serialize for ChessBoard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&write_fen(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for ChessBoard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fen = String::deserialize(deserializer)?;
+        try_read_fen(&fen).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MoveJson {
+    piece_type: PieceType,
+    uci: String,
+}
+
+// An object rather than a UCI string, see above
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MoveJson { piece_type: self.piece_type, uci: self.to_uci() }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = MoveJson::deserialize(deserializer)?;
+        let (old_position, new_position, promotion) =
+            parse_uci(&json.uci).ok_or_else(|| de::Error::custom(format!("Invalid UCI move {}", json.uci)))?;
+        if promotion.is_some() && json.piece_type != PieceType::Pawn {
+            return Err(de::Error::custom(format!("Only a pawn can promote, not a {:?} in {}", json.piece_type, json.uci)));
+        }
+        Ok(Move { piece_type: json.piece_type, old_position, new_position, promotion })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MoveNodeJson {
+    uci: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    nags: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pre_comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    variations: Vec<Vec<MoveNodeJson>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GameRecordJson {
+    #[serde(with = "ordered_tags")]
+    tags: Vec<(String, String)>,
+    starting_position: ChessBoard,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    moves: Vec<MoveNodeJson>,
+    result: GameResult,
+}
+
+impl Serialize for GameRecord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameRecordJson {
+            tags: self.tags.clone(),
+            starting_position: self.starting_board.clone(),
+            comment: self.comment.clone(),
+            moves: nodes_to_json(&self.moves),
+            result: self.result,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameRecord {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = GameRecordJson::deserialize(deserializer)?;
+        let moves = nodes_from_json(&json.starting_position, json.moves).map_err(de::Error::custom)?;
+        Ok(GameRecord {
+            tags: json.tags,
+            starting_board: json.starting_position,
+            comment: json.comment,
+            moves,
+            result: json.result,
+        })
+    }
+}
+
+fn nodes_to_json(nodes: &[MoveNode]) -> Vec<MoveNodeJson> {
+    nodes
+        .iter()
+        .map(|node| MoveNodeJson {
+            uci: node.chess_move.to_uci(),
+            nags: node.nags.clone(),
+            pre_comment: node.pre_comment.clone(),
+            comment: node.comment.clone(),
+            variations: node.variations.iter().map(|variation| nodes_to_json(variation)).collect(),
+        })
+        .collect()
+}
+
+// Replays the moves to find the moving pieces, variations start from the position before their move
+fn nodes_from_json(chess_board: &ChessBoard, nodes: Vec<MoveNodeJson>) -> Result<Vec<MoveNode>, String> {
+    let mut chess_board = chess_board.clone();
+    let mut result = Vec::new();
+    for node in nodes {
+        let chess_move = Move::from_uci(&chess_board, &node.uci)
+            .ok_or_else(|| format!("Move {} is not legal in the position {}", node.uci, write_fen(&chess_board)))?;
+        let variations = node
+            .variations
+            .into_iter()
+            .map(|variation| nodes_from_json(&chess_board, variation))
+            .collect::<Result<Vec<_>, String>>()?;
+        chess_board.make_move(&chess_move);
+        result.push(MoveNode { chess_move, nags: node.nags, pre_comment: node.pre_comment, comment: node.comment, variations });
+    }
+    Ok(result)
+}
+
+// Writes the tags as a JSON object and reads them back in the order they appear in the document
+mod ordered_tags {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(tags: &[(String, String)], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(tags.len()))?;
+        for (name, value) in tags {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(String, String)>, D::Error> {
+        struct TagVisitor;
+
+        impl<'de> Visitor<'de> for TagVisitor {
+            type Value = Vec<(String, String)>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of tag names to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut tags = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    tags.push(entry);
+                }
+                Ok(tags)
+            }
+        }
+
+        deserializer.deserialize_map(TagVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::piece::Color;
+    use crate::movesets::test_positions::KIWIPETE;
+    use crate::utils::fen_reader::read_fen;
+
+    #[test]
+    fn test_chess_board_json() {
+        let fen = KIWIPETE;
+        let json = serde_json::to_string(&read_fen(fen)).unwrap();
+        assert_eq!(format!("\"{}\"", fen), json);
+        let chess_board: ChessBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(fen, write_fen(&chess_board));
+        assert!(serde_json::from_str::<ChessBoard>("\"8/8 w - -\"").is_err());
+    }
+
+    #[test]
+    fn test_move_json() {
+        let chess_move = Move::from_uci(&ChessBoard::new(false), "g1f3").unwrap();
+        let json = serde_json::to_string(&chess_move).unwrap();
+        assert_eq!("{\"piece_type\":\"Knight\",\"uci\":\"g1f3\"}", json);
+        assert_eq!(chess_move, serde_json::from_str(&json).unwrap());
+        assert!(serde_json::from_str::<Move>("{\"piece_type\":\"Knight\",\"uci\":\"g1\"}").is_err());
+        assert!(serde_json::from_str::<Move>("{\"piece_type\":\"Knight\",\"uci\":\"e2e4q\"}").is_err());
+        let promotion: Move = serde_json::from_str("{\"piece_type\":\"Pawn\",\"uci\":\"a7a8n\"}").unwrap();
+        assert_eq!(Some(PieceType::Knight), promotion.promotion);
+    }
+
+    #[test]
+    fn test_enum_json() {
+        assert_eq!("\"Black\"", serde_json::to_string(&Color::Black).unwrap());
+        assert_eq!(PieceType::Queen, serde_json::from_str("\"Queen\"").unwrap());
+        assert_eq!("\"Draw\"", serde_json::to_string(&GameResult::Draw).unwrap());
+    }
+
+    #[test]
+    fn test_game_record_json() {
+        let chess_board = ChessBoard::new(false);
+        let mut record = GameRecord::new(chess_board.clone());
+        record.set_tag("White", "Morphy");
+        record.set_tag("Event", "Paris");
+        let mut first = MoveNode::new(Move::from_uci(&chess_board, "e2e4").unwrap());
+        first.nags.push(1);
+        let mut after_e4 = chess_board.clone();
+        after_e4.make_move(&first.chess_move);
+        let mut second = MoveNode::new(Move::from_uci(&after_e4, "e7e5").unwrap());
+        second.variations.push(vec![MoveNode::new(Move::from_uci(&after_e4, "c7c5").unwrap())]);
+        second.comment = Some("Open game".to_string());
+        record.moves = vec![first, second];
+        record.result = GameResult::WhiteWins;
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            "{\"tags\":{\"White\":\"Morphy\",\"Event\":\"Paris\"},\
+            \"starting_position\":\"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\",\
+            \"moves\":[{\"uci\":\"e2e4\",\"nags\":[1]},{\"uci\":\"e7e5\",\"comment\":\"Open game\",\"variations\":[[{\"uci\":\"c7c5\"}]]}],\
+            \"result\":\"WhiteWins\"}",
+            json
+        );
+        let read: GameRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(record, read);
+    }
+
+    #[test]
+    fn test_game_record_json_illegal_move() {
+        let json = "{\"tags\":{},\"starting_position\":\"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\",\
+            \"moves\":[{\"uci\":\"e2e5\"}],\"result\":\"Unknown\"}";
+        assert!(serde_json::from_str::<GameRecord>(json).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use super_rusty::chess::chess_board::ChessBoard;
+use super_rusty::chess_game::play_random_games;
+use super_rusty::utils::fen_reader::read_fen;
+
+// Plays random games on the bitboard board and the mailbox reference in chess_game.rs and compares
+// the legal moves and resulting positions after every move. The long runs are ignored by default,
+// run them with `cargo test --release --test differential -- --include-ignored`.
+
+// Positions where castling, en passant and promotions come up quickly
+const STARTING_FENS: [&str; 4] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+fn assert_same_moves(start: &ChessBoard, games: usize, plies: usize, seed: u64) {
+    if let Err(mismatch) = play_random_games(start, games, plies, seed) {
+        panic!("{}", mismatch);
+    }
+}
+
+#[test]
+fn differential_random_games() {
+    assert_same_moves(&ChessBoard::new(false), 2, 80, 1);
+    for (seed, fen) in STARTING_FENS.iter().enumerate() {
+        assert_same_moves(&read_fen(fen), 2, 20, seed as u64);
+    }
+}
+
+#[test]
+#[ignore]
+fn differential_thousands_of_random_games() {
+    assert_same_moves(&ChessBoard::new(false), 2000, 200, 2);
+    for (seed, fen) in STARTING_FENS.iter().enumerate() {
+        assert_same_moves(&read_fen(fen), 500, 100, seed as u64 + 10);
+    }
+}
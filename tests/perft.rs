@@ -0,0 +1,74 @@
+use super_rusty::utils::fen_reader::read_fen;
+
+// The perft positions from the Chess Programming Wiki (https://www.chessprogramming.org/Perft_Results)
+// with their published node counts. The deeper counts take long without optimizations and are
+// ignored, run them with `cargo test --release --test perft -- --include-ignored`.
+
+const INITIAL: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let chess_board = read_fen(fen);
+    for (depth, &nodes) in expected.iter().enumerate() {
+        let depth = depth as u32 + 1;
+        if nodes != chess_board.perft(depth) {
+            let divide: Vec<String> = chess_board
+                .divide(depth)
+                .iter()
+                .map(|(chess_move, nodes)| format!("{}: {}", chess_move.to_uci(), nodes))
+                .collect();
+            panic!("perft({}) of {} should be {}, divide:\n{}", depth, fen, nodes, divide.join("\n"));
+        }
+    }
+}
+
+fn assert_deep_perft(fen: &str, depth: u32, expected: u64) {
+    assert_eq!(expected, read_fen(fen).perft_parallel(depth), "perft({}) of {}", depth, fen);
+}
+
+#[test]
+fn perft_initial_position() {
+    assert_perft(INITIAL, &[20, 400, 8902]);
+}
+
+#[test]
+fn perft_kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862]);
+}
+
+#[test]
+fn perft_position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238]);
+}
+
+#[test]
+fn perft_position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9467]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467]);
+}
+
+#[test]
+fn perft_position_5() {
+    assert_perft(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+fn perft_position_6() {
+    assert_perft(POSITION_6, &[46, 2079, 89890]);
+}
+
+#[test]
+#[ignore]
+fn perft_deep() {
+    assert_deep_perft(INITIAL, 5, 4865609);
+    assert_deep_perft(KIWIPETE, 4, 4085603);
+    assert_deep_perft(POSITION_3, 6, 11030083);
+    assert_deep_perft(POSITION_4, 5, 15833292);
+    assert_deep_perft(POSITION_5, 4, 2103487);
+    assert_deep_perft(POSITION_6, 4, 3894594);
+}